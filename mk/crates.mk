@@ -50,7 +50,7 @@
 ################################################################################
 
 TARGET_CRATES := libc std term \
-                 getopts collections test rand \
+                 getopts collections test rand serialize \
                  compiler_builtins core alloc \
                  std_unicode rustc_bitflags \
 		 alloc_system alloc_jemalloc \
@@ -61,7 +61,7 @@ RUSTC_CRATES := rustc rustc_typeck rustc_mir rustc_borrowck rustc_resolve rustc_
                 rustc_plugin rustc_metadata rustc_passes rustc_save_analysis \
                 rustc_const_eval rustc_const_math rustc_incremental proc_macro
 HOST_CRATES := syntax syntax_ext proc_macro_plugin syntax_pos $(RUSTC_CRATES) \
-		rustdoc fmt_macros flate arena graphviz log serialize
+		rustdoc fmt_macros flate arena graphviz log
 TOOLS := compiletest rustdoc rustc rustbook error_index_generator
 
 DEPS_core :=
@@ -92,9 +92,9 @@ DEPS_getopts := std
 DEPS_graphviz := std
 DEPS_log := std
 DEPS_num := std
-DEPS_serialize := std log
+DEPS_serialize := std
 DEPS_term := std
-DEPS_test := std getopts term native:rust_test_helpers
+DEPS_test := std getopts rand serialize term native:rust_test_helpers
 
 DEPS_syntax := std term serialize log arena libc rustc_bitflags std_unicode rustc_errors \
 			syntax_pos rustc_data_structures
@@ -212,7 +212,9 @@ pub fn compiletest(build: &Build,
            .arg("--cxx").arg(build.cxx(target))
            .arg("--cflags").arg(build.cflags(target).join(" "))
            .arg("--llvm-components").arg(llvm_components.trim())
-           .arg("--llvm-cxxflags").arg(llvm_cxxflags.trim());
+           .arg("--llvm-cxxflags").arg(llvm_cxxflags.trim())
+           .arg("--apply-suggestions-path")
+           .arg(build.tool(&Compiler::new(0, compiler.host), "apply-suggestions"));
     } else {
         cmd.arg("--cc").arg("")
            .arg("--cxx").arg("")
@@ -369,7 +369,6 @@ pub fn build_rules<'a>(build: &'a Build) -> Rules {
               "run-fail", "run-fail-fulldeps");
         suite("check-cfail-full", "src/test/compile-fail-fulldeps",
               "compile-fail", "compile-fail-fulldeps");
-        suite("check-rmake", "src/test/run-make", "run-make", "run-make");
         suite("check-rustdoc", "src/test/rustdoc", "rustdoc", "rustdoc");
         suite("check-pretty", "src/test/pretty", "pretty", "pretty");
         suite("check-pretty-rpass", "src/test/run-pass/pretty", "pretty",
@@ -384,6 +383,17 @@ pub fn build_rules<'a>(build: &'a Build) -> Rules {
               "pretty", "run-fail-fulldeps");
     }
 
+    rules.test("check-rmake", "src/test/run-make")
+         .dep(|s| s.name("librustc"))
+         .dep(|s| s.name("test-helpers"))
+         .dep(|s| s.name("tool-compiletest").target(s.host).stage(0))
+         .dep(|s| s.name("tool-apply-suggestions").target(s.host).stage(0))
+         .default(true)
+         .host(true)
+         .run(move |s| {
+             check::compiletest(build, &s.compiler(), s.target, "run-make", "run-make")
+         });
+
     for (krate, path, _default) in krates("std_shim") {
         rules.test(&krate.test_step, path)
              .dep(|s| s.name("libtest"))
@@ -516,6 +526,9 @@ pub fn build_rules<'a>(build: &'a Build) -> Rules {
     rules.build("tool-build-manifest", "src/tools/build-manifest")
          .dep(|s| s.name("libstd"))
          .run(move |s| compile::tool(build, s.stage, s.target, "build-manifest"));
+    rules.build("tool-apply-suggestions", "src/tools/apply-suggestions")
+         .dep(|s| s.name("libstd"))
+         .run(move |s| compile::tool(build, s.stage, s.target, "apply-suggestions"));
 
     // ========================================================================
     // Documentation targets
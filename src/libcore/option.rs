@@ -287,6 +287,7 @@ impl<T> Option<T> {
     /// ```
     #[inline]
     #[stable(feature = "rust1", since = "1.0.0")]
+    #[cfg_attr(not(stage0), lang = "option_expect")]
     pub fn expect(self, msg: &str) -> T {
         match self {
             Some(val) => val,
@@ -317,6 +318,7 @@ impl<T> Option<T> {
     /// ```
     #[inline]
     #[stable(feature = "rust1", since = "1.0.0")]
+    #[cfg_attr(not(stage0), lang = "option_unwrap")]
     pub fn unwrap(self) -> T {
         match self {
             Some(val) => val,
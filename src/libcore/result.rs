@@ -739,6 +739,7 @@ impl<T, E: fmt::Debug> Result<T, E> {
     /// ```
     #[inline]
     #[stable(feature = "rust1", since = "1.0.0")]
+    #[cfg_attr(not(stage0), lang = "result_unwrap")]
     pub fn unwrap(self) -> T {
         match self {
             Ok(t) => t,
@@ -763,6 +764,7 @@ impl<T, E: fmt::Debug> Result<T, E> {
     /// ```
     #[inline]
     #[stable(feature = "result_expect", since = "1.4.0")]
+    #[cfg_attr(not(stage0), lang = "result_expect")]
     pub fn expect(self, msg: &str) -> T {
         match self {
             Ok(t) => t,
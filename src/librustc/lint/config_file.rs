@@ -0,0 +1,142 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The lint configuration file given with `--lint-config`.
+//!
+//! The file is a small subset of TOML: a `[lints]` table mapping the names
+//! of lints or lint groups to their levels.
+//!
+//! ```toml
+//! [lints]
+//! unused-imports = "deny"
+//! bad_style = "allow"     # lint groups work too
+//! ```
+//!
+//! The `[lints]` header may be left out. Levels set here are applied before
+//! those of `-A`, `-W`, `-D` and `-F`, so the command line and then the
+//! attributes of the crate can override them, and `--cap-lints` caps them
+//! like any other level.
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use super::Level;
+
+/// Reads the lint levels set in the configuration file at `path`.
+pub fn read(path: &Path) -> Result<Vec<(String, Level)>, String> {
+    let mut contents = String::new();
+    File::open(path).and_then(|mut f| f.read_to_string(&mut contents)).map_err(|e| {
+        format!("failed to read lint configuration file `{}`: {}", path.display(), e)
+    })?;
+    parse(&contents).map_err(|e| {
+        format!("invalid lint configuration file `{}`: {}", path.display(), e)
+    })
+}
+
+/// Parses the contents of a lint configuration file into `(name, level)`
+/// pairs, in the order they were written. Names are normalized to use
+/// underscores like those given on the command line.
+pub fn parse(contents: &str) -> Result<Vec<(String, Level)>, String> {
+    let mut levels = vec![];
+    let mut seen = HashSet::new();
+
+    for (i, line) in contents.lines().enumerate() {
+        let line_number = i + 1;
+        let line = match line.find('#') {
+            Some(pos) => &line[..pos],
+            None => line,
+        }.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if line.starts_with('[') {
+            if !line.ends_with(']') {
+                return Err(format!("line {}: unterminated table header", line_number));
+            }
+            let table = line[1..line.len() - 1].trim();
+            if table != "lints" {
+                return Err(format!("line {}: unknown table `[{}]`, expected `[lints]`",
+                                   line_number, table));
+            }
+            continue;
+        }
+
+        let (name, value) = match line.find('=') {
+            Some(pos) => (line[..pos].trim(), line[pos + 1..].trim()),
+            None => {
+                return Err(format!("line {}: expected `name = \"level\"`", line_number));
+            }
+        };
+        let name = unquote(name).unwrap_or(name);
+        if name.is_empty() {
+            return Err(format!("line {}: missing lint name", line_number));
+        }
+        let level = unquote(value).and_then(Level::from_str).ok_or_else(|| {
+            format!("line {}: unknown lint level {} for `{}`, expected one of \
+                     \"allow\", \"warn\", \"deny\" or \"forbid\"",
+                    line_number, value, name)
+        })?;
+
+        let name = name.replace("-", "_");
+        if !seen.insert(name.clone()) {
+            return Err(format!("line {}: the level of `{}` is set more than once",
+                               line_number, name));
+        }
+        levels.push((name, level));
+    }
+
+    Ok(levels)
+}
+
+/// Strips the quotes of a TOML basic or literal string.
+fn unquote(s: &str) -> Option<&str> {
+    if s.len() >= 2 && ((s.starts_with('"') && s.ends_with('"')) ||
+                        (s.starts_with('\'') && s.ends_with('\''))) {
+        Some(&s[1..s.len() - 1])
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+    use lint::{Allow, Warn, Deny, Forbid};
+
+    #[test]
+    fn parse_levels() {
+        let contents = "# Levels for the whole project.\n\
+                        [lints]\n\
+                        unused-imports = \"deny\"\n\
+                        \n\
+                        bad_style = 'allow'   # a group\n\
+                        \"missing_docs\" = \"warn\"\n\
+                        unsafe_code=\"forbid\"\n";
+        assert_eq!(parse(contents).unwrap(),
+                   vec![("unused_imports".to_string(), Deny),
+                        ("bad_style".to_string(), Allow),
+                        ("missing_docs".to_string(), Warn),
+                        ("unsafe_code".to_string(), Forbid)]);
+        assert_eq!(parse("dead_code = \"warn\"").unwrap(),
+                   vec![("dead_code".to_string(), Warn)]);
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(parse("dead_code = \"loud\"").is_err());
+        assert!(parse("dead_code = deny").is_err());
+        assert!(parse("dead_code").is_err());
+        assert!(parse("[package]\nname = \"foo\"").is_err());
+        assert!(parse("dead_code = \"deny\"\ndead-code = \"allow\"").is_err());
+    }
+}
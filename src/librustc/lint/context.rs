@@ -31,7 +31,7 @@ use ty::{self, TyCtxt};
 use session::{config, early_error, Session};
 use lint::{Level, LevelSource, Lint, LintId, LintPass, LintSource};
 use lint::{EarlyLintPassObject, LateLintPassObject};
-use lint::{Default, CommandLine, Node, Allow, Warn, Deny, Forbid};
+use lint::{Default, CommandLine, ConfigFile, Node, Allow, Warn, Deny, Forbid};
 use lint::builtin;
 use rustc_serialize::{Decoder, Decodable, Encoder, Encodable};
 use util::nodemap::FxHashMap;
@@ -297,33 +297,21 @@ impl LintStore {
     }
 
     pub fn process_command_line(&mut self, sess: &Session) {
+        // The levels of the configuration file come first so that those given
+        // on the command line override them.
+        for &(ref lint_name, level) in &sess.opts.lint_config {
+            check_lint_name_config(sess, self, &lint_name[..], level);
+
+            let lint_flag_val = Symbol::intern(&lint_name);
+            self.set_level_by_name(sess, &lint_name[..], (level, ConfigFile(lint_flag_val)));
+        }
+
         for &(ref lint_name, level) in &sess.opts.lint_opts {
             check_lint_name_cmdline(sess, self,
                                     &lint_name[..], level);
 
             let lint_flag_val = Symbol::intern(&lint_name);
-            match self.find_lint(&lint_name[..], sess, None) {
-                Ok(lint_id) => self.set_level(lint_id, (level, CommandLine(lint_flag_val))),
-                Err(FindLintError::Removed) => { }
-                Err(_) => {
-                    match self.lint_groups.iter().map(|(&x, pair)| (x, pair.0.clone()))
-                                                 .collect::<FxHashMap<&'static str,
-                                                                      Vec<LintId>>>()
-                                                 .get(&lint_name[..]) {
-                        Some(v) => {
-                            v.iter()
-                             .map(|lint_id: &LintId|
-                                     self.set_level(*lint_id, (level, CommandLine(lint_flag_val))))
-                             .collect::<Vec<()>>();
-                        }
-                        None => {
-                            // The lint or lint group doesn't exist.
-                            // This is an error, but it was handled
-                            // by check_lint_name_cmdline.
-                        }
-                    }
-                }
-            }
+            self.set_level_by_name(sess, &lint_name[..], (level, CommandLine(lint_flag_val)));
         }
 
         self.lint_cap = sess.opts.lint_cap;
@@ -333,6 +321,33 @@ impl LintStore {
             }
         }
     }
+
+    /// Sets the level of the lint or of all lints of the group called
+    /// `lint_name`.
+    fn set_level_by_name(&mut self, sess: &Session, lint_name: &str, lvlsrc: LevelSource) {
+        match self.find_lint(lint_name, sess, None) {
+            Ok(lint_id) => self.set_level(lint_id, lvlsrc),
+            Err(FindLintError::Removed) => { }
+            Err(_) => {
+                match self.lint_groups.iter().map(|(&x, pair)| (x, pair.0.clone()))
+                                             .collect::<FxHashMap<&'static str,
+                                                                  Vec<LintId>>>()
+                                             .get(lint_name) {
+                    Some(v) => {
+                        v.iter()
+                         .map(|lint_id: &LintId| self.set_level(*lint_id, lvlsrc))
+                         .collect::<Vec<()>>();
+                    }
+                    None => {
+                        // The lint or lint group doesn't exist.
+                        // This is an error, but it was handled
+                        // by check_lint_name_cmdline or
+                        // check_lint_name_config.
+                    }
+                }
+            }
+        }
+    }
 }
 
 /// Context for lint checking after type checking.
@@ -487,6 +502,19 @@ pub fn raw_struct_lint<'a, S>(sess: &'a Session,
                                   flag, hyphen_case_lint_name, flag, hyphen_case_flag_val));
             }
         },
+        ConfigFile(lint_flag_val) => {
+            let path = sess.opts.lint_config_path.as_ref().map_or(String::new(), |path| {
+                format!(" `{}`", path.display())
+            });
+            if lint_flag_val.as_str().deref() == name {
+                err.note(&format!("set to `{}` in the lint configuration file{}",
+                                  level.as_str(), path));
+            } else {
+                err.note(&format!("`{} = \"{}\"` implied by `{} = \"{}\"` in the lint \
+                                   configuration file{}",
+                                  name, level.as_str(), lint_flag_val, level.as_str(), path));
+            }
+        },
         Node(lint_attr_name, src) => {
             def = Some(src);
             if lint_attr_name.as_str().deref() != name {
@@ -683,6 +711,10 @@ pub trait LintContext<'tcx>: Sized {
                         },
                         LintSource::CommandLine(_) => {
                             diag_builder.note("`forbid` lint level was set on command line")
+                        },
+                        LintSource::ConfigFile(_) => {
+                            diag_builder.note("`forbid` lint level was set in the lint \
+                                               configuration file")
                         }
                     }.emit()
                 } else if now != level {
@@ -1215,6 +1247,27 @@ fn check_lint_name_cmdline(sess: &Session, lint_cx: &LintStore,
     }
 }
 
+fn check_lint_name_config(sess: &Session, lint_cx: &LintStore,
+                          lint_name: &str, level: Level) {
+    let db = match check_lint_name(lint_cx, lint_name) {
+        CheckLintNameResult::Ok => None,
+        CheckLintNameResult::Warning(ref msg) => {
+            Some(sess.struct_warn(msg))
+        },
+        CheckLintNameResult::NoLint => {
+            Some(sess.struct_err(&format!("unknown lint: `{}`", lint_name)))
+        }
+    };
+
+    if let Some(mut db) = db {
+        let path = sess.opts.lint_config_path.as_ref().map_or(String::new(), |path| {
+            format!(" `{}`", path.display())
+        });
+        db.note(&format!("requested with `{} = \"{}\"` in the lint configuration file{}",
+                         lint_name, level.as_str(), path));
+        db.emit();
+    }
+}
 
 /// Perform lint checking on a crate.
 ///
@@ -343,10 +343,14 @@ pub enum LintSource {
 
     /// Lint level was set by a command-line flag.
     CommandLine(Symbol),
+
+    /// Lint level was set in the file given with `--lint-config`.
+    ConfigFile(Symbol),
 }
 
 pub type LevelSource = (Level, LintSource);
 
 pub mod builtin;
+pub mod config_file;
 mod context;
 mod table;
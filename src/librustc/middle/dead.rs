@@ -11,6 +11,11 @@
 // This implements the dead-code warning pass. It follows middle::reachable
 // closely. The idea is that all reachable symbols are live, codes called
 // from live codes are live, and everything else is dead.
+//
+// With `-Z dead-code-workspace=DIR`, exported symbols are only live if one of
+// the other crates of a workspace uses them. Their uses are read from the
+// save-analysis data of those crates, which must be in DIR and have been made
+// against this very build of the crate. Items are found by their def paths.
 
 use dep_graph::DepNode;
 use hir::map as hir_map;
@@ -21,9 +26,15 @@ use hir::itemlikevisit::ItemLikeVisitor;
 use middle::privacy;
 use ty::{self, TyCtxt};
 use hir::def::Def;
-use hir::def_id::{DefId};
+use hir::def_id::{DefId, DefIndex, LOCAL_CRATE};
+use hir::svh::Svh;
 use lint;
-use util::nodemap::FxHashSet;
+use util::nodemap::{FxHashMap, FxHashSet};
+
+use rustc_serialize::json::{self, Json};
+
+use std::fs::{self, File};
+use std::path::Path;
 
 use syntax::{ast, codemap};
 use syntax::attr;
@@ -362,11 +373,17 @@ impl<'v, 'k> ItemLikeVisitor<'v> for LifeSeeder<'k> {
 
 fn create_and_seed_worklist<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
                                       access_levels: &privacy::AccessLevels,
-                                      krate: &hir::Crate)
+                                      krate: &hir::Crate,
+                                      crate_hash: Svh)
                                       -> Vec<ast::NodeId> {
     let mut worklist = Vec::new();
-    for (id, _) in &access_levels.map {
-        worklist.push(*id);
+    match tcx.sess.opts.debugging_opts.dead_code_workspace {
+        Some(ref dir) => worklist.extend(workspace_uses(tcx, Path::new(dir), crate_hash)),
+        None => {
+            for (id, _) in &access_levels.map {
+                worklist.push(*id);
+            }
+        }
     }
 
     // Seed entry point
@@ -384,11 +401,99 @@ fn create_and_seed_worklist<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
     return life_seeder.worklist;
 }
 
+/// Returns the items of the local crate used by the other crates whose
+/// save-analysis data is in `dir`. Data made against another build of the
+/// local crate than this one, `crate_hash`, is rejected.
+fn workspace_uses<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                            dir: &Path,
+                            crate_hash: Svh)
+                            -> Vec<ast::NodeId> {
+    let crate_name = tcx.crate_name(LOCAL_CRATE).as_str();
+    let disambiguator = tcx.sess.local_crate_disambiguator().as_str();
+    let crate_hash = crate_hash.to_string();
+    let mut def_paths = None;
+    let mut uses = Vec::new();
+
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            tcx.sess.err(&format!("could not read save-analysis directory `{}`: {}",
+                                  dir.display(), e));
+            return uses;
+        }
+    };
+    for entry in entries {
+        let path = match entry {
+            Ok(entry) => entry.path(),
+            Err(_) => continue,
+        };
+        if path.extension().map_or(true, |ext| ext != "json") {
+            continue;
+        }
+        let analysis = match read_analysis(&path) {
+            Ok(analysis) => analysis,
+            Err(e) => {
+                tcx.sess.warn(&format!("could not read save-analysis data `{}`: {}",
+                                       path.display(), e));
+                continue;
+            }
+        };
+
+        // The local crate as the crate of this data knows it, if it uses it
+        // at all. Its own uses are found by the usual liveness analysis.
+        let prelude = analysis.find("prelude");
+        let external_crates = prelude.and_then(|p| p.find("external_crates"))
+                                     .and_then(Json::as_array);
+        let local_crate = external_crates.into_iter().flat_map(|crates| crates).find(|c| {
+            c.find("name").and_then(Json::as_string) == Some(&*crate_name) &&
+            c.find("disambiguator").and_then(Json::as_string) == Some(&*disambiguator)
+        });
+        let local_crate = match local_crate {
+            Some(local_crate) => local_crate,
+            None => continue,
+        };
+        if local_crate.find("hash").and_then(Json::as_string) != Some(&*crate_hash) {
+            tcx.sess.err(&format!("save-analysis data `{}` was made against another build \
+                                   of `{}`, it has to be made again",
+                                  path.display(), crate_name));
+            continue;
+        }
+
+        if def_paths.is_none() {
+            def_paths = Some(local_def_paths(tcx));
+        }
+        let def_paths = def_paths.as_ref().unwrap();
+        let refs = analysis.find("refs").and_then(Json::as_array);
+        for ref_path in refs.into_iter()
+                            .flat_map(|refs| refs)
+                            .filter_map(|r| r.find("ref_path"))
+                            .filter_map(Json::as_string) {
+            uses.extend(def_paths.get(ref_path).cloned());
+        }
+    }
+    uses
+}
+
+/// The items of the local crate by their def paths, in the form the
+/// save-analysis data gives them.
+fn local_def_paths<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>) -> FxHashMap<String, ast::NodeId> {
+    (0..tcx.hir.definitions().len()).filter_map(|index| {
+        let def_id = DefId::local(DefIndex::new(index));
+        tcx.hir.as_local_node_id(def_id).map(|id| (tcx.def_path(def_id).to_string(tcx), id))
+    }).collect()
+}
+
+fn read_analysis(path: &Path) -> Result<Json, String> {
+    let mut file = File::open(path).map_err(|e| e.to_string())?;
+    json::from_reader(&mut file).map_err(|e| e.to_string())
+}
+
 fn find_live<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
                        access_levels: &privacy::AccessLevels,
-                       krate: &hir::Crate)
+                       krate: &hir::Crate,
+                       crate_hash: Svh)
                        -> Box<FxHashSet<ast::NodeId>> {
-    let worklist = create_and_seed_worklist(tcx, access_levels, krate);
+    let worklist = create_and_seed_worklist(tcx, access_levels, krate, crate_hash);
     let mut symbol_visitor = MarkSymbolVisitor {
         worklist: worklist,
         tcx: tcx,
@@ -414,6 +519,7 @@ fn get_struct_ctor_id(item: &hir::Item) -> Option<ast::NodeId> {
 
 struct DeadVisitor<'a, 'tcx: 'a> {
     tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    access_levels: &'a privacy::AccessLevels,
     live_symbols: Box<FxHashSet<ast::NodeId>>,
 }
 
@@ -498,12 +604,15 @@ impl<'a, 'tcx> DeadVisitor<'a, 'tcx> {
                       name: ast::Name,
                       node_type: &str) {
         if !name.as_str().starts_with("_") {
+            let msg = if self.tcx.sess.opts.debugging_opts.dead_code_workspace.is_some() &&
+                         self.access_levels.is_exported(id) {
+                format!("public {} is never used in the workspace: `{}`", node_type, name)
+            } else {
+                format!("{} is never used: `{}`", node_type, name)
+            };
             self.tcx
                 .sess
-                .add_lint(lint::builtin::DEAD_CODE,
-                          id,
-                          span,
-                          format!("{} is never used: `{}`", node_type, name));
+                .add_lint(lint::builtin::DEAD_CODE, id, span, msg);
         }
     }
 }
@@ -594,10 +703,15 @@ impl<'a, 'tcx> Visitor<'tcx> for DeadVisitor<'a, 'tcx> {
 }
 
 pub fn check_crate<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
-                             access_levels: &privacy::AccessLevels) {
+                             access_levels: &privacy::AccessLevels,
+                             crate_hash: Svh) {
     let _task = tcx.dep_graph.in_task(DepNode::DeadCheck);
     let krate = tcx.hir.krate();
-    let live_symbols = find_live(tcx, access_levels, krate);
-    let mut visitor = DeadVisitor { tcx: tcx, live_symbols: live_symbols };
+    let live_symbols = find_live(tcx, access_levels, krate, crate_hash);
+    let mut visitor = DeadVisitor {
+        tcx: tcx,
+        access_levels: access_levels,
+        live_symbols: live_symbols,
+    };
     intravisit::walk_crate(&mut visitor, krate);
 }
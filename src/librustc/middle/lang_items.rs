@@ -126,26 +126,16 @@ struct LanguageItemCollector<'a, 'tcx: 'a> {
 
 impl<'a, 'v, 'tcx> ItemLikeVisitor<'v> for LanguageItemCollector<'a, 'tcx> {
     fn visit_item(&mut self, item: &hir::Item) {
-        if let Some(value) = extract(&item.attrs) {
-            let item_index = self.item_refs.get(&*value.as_str()).cloned();
-
-            if let Some(item_index) = item_index {
-                self.collect_item(item_index, self.hir_map.local_def_id(item.id))
-            } else {
-                let span = self.hir_map.span(item.id);
-                span_err!(self.session, span, E0522,
-                          "definition of an unknown language item: `{}`.",
-                          value);
-            }
-        }
+        self.check_attrs(&item.attrs, item.id);
     }
 
     fn visit_trait_item(&mut self, _trait_item: &hir::TraitItem) {
-        // at present, lang items are always items, not trait items
+        // at present, lang items are never trait items
     }
 
-    fn visit_impl_item(&mut self, _impl_item: &hir::ImplItem) {
-        // at present, lang items are always items, not impl items
+    fn visit_impl_item(&mut self, impl_item: &hir::ImplItem) {
+        // e.g. `Option::unwrap`, which lints look for
+        self.check_attrs(&impl_item.attrs, impl_item.id);
     }
 }
 
@@ -164,6 +154,21 @@ impl<'a, 'tcx> LanguageItemCollector<'a, 'tcx> {
         }
     }
 
+    fn check_attrs(&mut self, attrs: &[ast::Attribute], id: ast::NodeId) {
+        if let Some(value) = extract(attrs) {
+            let item_index = self.item_refs.get(&*value.as_str()).cloned();
+
+            if let Some(item_index) = item_index {
+                self.collect_item(item_index, self.hir_map.local_def_id(id))
+            } else {
+                let span = self.hir_map.span(id);
+                span_err!(self.session, span, E0522,
+                          "definition of an unknown language item: `{}`.",
+                          value);
+            }
+        }
+    }
+
     pub fn collect_item(&mut self, item_index: usize,
                         item_def_id: DefId) {
         // Check for duplicates.
@@ -360,6 +365,11 @@ language_item_table! {
     NonZeroItem,                     "non_zero",                non_zero;
 
     DebugTraitLangItem,              "debug_trait",             debug_trait;
+
+    OptionUnwrapFnLangItem,          "option_unwrap",           option_unwrap_fn;
+    OptionExpectFnLangItem,          "option_expect",           option_expect_fn;
+    ResultUnwrapFnLangItem,          "result_unwrap",           result_unwrap_fn;
+    ResultExpectFnLangItem,          "result_expect",           result_expect_fn;
 }
 
 impl<'a, 'tcx, 'gcx> ty::TyCtxt<'a, 'tcx, 'gcx> {
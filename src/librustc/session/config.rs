@@ -122,6 +122,7 @@ impl OutputType {
 pub enum ErrorOutputType {
     HumanReadable(ColorConfig),
     Json,
+    Sarif,
 }
 
 impl Default for ErrorOutputType {
@@ -274,6 +275,10 @@ top_level_options!(
         debuginfo: DebugInfoLevel [TRACKED],
         lint_opts: Vec<(String, lint::Level)> [TRACKED],
         lint_cap: Option<lint::Level> [TRACKED],
+        // The levels read from the `--lint-config` file. The file itself is
+        // not tracked, only what was read from it.
+        lint_config: Vec<(String, lint::Level)> [TRACKED],
+        lint_config_path: Option<PathBuf> [UNTRACKED],
         describe_lints: bool [UNTRACKED],
         output_types: OutputTypes [TRACKED],
         // FIXME(mw): We track this for now but it actually doesn't make too
@@ -287,6 +292,11 @@ top_level_options!(
 
         test: bool [TRACKED],
         error_format: ErrorOutputType [UNTRACKED],
+        // Abort compilation once this many errors have been reported.
+        error_limit: Option<usize> [UNTRACKED],
+        // Report the diagnostics with the same code, message and origin
+        // together, and count the errors by code at the end.
+        group_diagnostics: bool [UNTRACKED],
 
         // if Some, enable incremental compilation, using the given
         // directory to store intermediate results
@@ -446,6 +456,8 @@ pub fn basic_options() -> Options {
         debuginfo: NoDebugInfo,
         lint_opts: Vec::new(),
         lint_cap: None,
+        lint_config: Vec::new(),
+        lint_config_path: None,
         describe_lints: false,
         output_types: OutputTypes(BTreeMap::new()),
         search_paths: SearchPaths::new(),
@@ -457,6 +469,8 @@ pub fn basic_options() -> Options {
         prints: Vec::new(),
         cg: basic_codegen_options(),
         error_format: ErrorOutputType::default(),
+        error_limit: None,
+        group_diagnostics: false,
         externs: Externs(BTreeMap::new()),
         crate_name: None,
         alt_std_name: None,
@@ -876,6 +890,9 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
     save_analysis_api: bool = (false, parse_bool, [UNTRACKED],
         "write syntax and type analysis information for opaque libraries (in JSON format), \
          in addition to normal output"),
+    dead_code_workspace: Option<String> = (None, parse_opt_string, [UNTRACKED],
+        "report public items as dead code unless used by one of the crates whose \
+         save-analysis data (in JSON format) is in this directory"),
     print_move_fragments: bool = (false, parse_bool, [UNTRACKED],
         "print out move-fragment data for every fn"),
     flowgraph_print_loans: bool = (false, parse_bool, [UNTRACKED],
@@ -897,6 +914,8 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
           "treat all errors that occur as bugs"),
     continue_parse_after_error: bool = (false, parse_bool, [TRACKED],
           "attempt to recover from parse errors (experimental)"),
+    macro_backtrace: Option<usize> = (None, parse_opt_uint, [UNTRACKED],
+          "show a snippet of each of the first N macro expansions an error comes from"),
     incremental: Option<String> = (None, parse_opt_string, [UNTRACKED],
           "enable incremental compilation (experimental)"),
     incremental_cc: bool = (false, parse_bool, [UNTRACKED],
@@ -1221,11 +1240,19 @@ pub fn rustc_optgroups() -> Vec<RustcOptGroup> {
         opt::multi_ubnr("Z", "", "Set internal debugging options", "FLAG"),
         opt::opt_s("", "error-format",
                       "How errors and other messages are produced",
-                      "human|json"),
+                      "human|json|sarif"),
         opt::opt_s("", "color", "Configure coloring of output:
                                  auto   = colorize, if output goes to a tty (default);
                                  always = always colorize output;
                                  never  = never colorize output", "auto|always|never"),
+        opt::opt("", "explain-search", "Search the detailed explanations of error messages",
+                 "TERMS"),
+        opt::opt("", "error-limit", "Abort compilation after N errors", "N"),
+        opt::flag("", "group-diagnostics", "Report errors and warnings with the same code, \
+                                            message and origin together, and summarize the \
+                                            errors by code"),
+        opt::opt("", "lint-config", "Read lint levels from a file. Levels given \
+                                     on the command line take precedence", "PATH"),
 
         opt::flagopt_ubnr("", "pretty",
                           "Pretty-print the input instead of compiling;
@@ -1298,13 +1325,14 @@ pub fn build_session_options_and_crate_config(matches: &getopts::Matches)
         match matches.opt_str("error-format").as_ref().map(|s| &s[..]) {
             Some("human")   => ErrorOutputType::HumanReadable(color),
             Some("json") => ErrorOutputType::Json,
+            Some("sarif") => ErrorOutputType::Sarif,
 
             None => ErrorOutputType::HumanReadable(color),
 
             Some(arg) => {
                 early_error(ErrorOutputType::HumanReadable(color),
-                            &format!("argument for --error-format must be human or json (instead \
-                                      was `{}`)",
+                            &format!("argument for --error-format must be human, json or \
+                                      sarif (instead was `{}`)",
                                      arg))
             }
         }
@@ -1312,6 +1340,20 @@ pub fn build_session_options_and_crate_config(matches: &getopts::Matches)
         ErrorOutputType::HumanReadable(color)
     };
 
+    // Like error-format, these are unstable options.
+    let error_limit = matches.opts_str(&["error-limit".to_owned()]).map(|limit| {
+        match limit.parse() {
+            Ok(n) if n > 0 => n,
+            _ => {
+                early_error(error_format,
+                            &format!("argument for --error-limit must be a positive \
+                                      integer (instead was `{}`)",
+                                     limit))
+            }
+        }
+    });
+    let group_diagnostics = matches.opts_present(&["group-diagnostics".to_owned()]);
+
     let unparsed_crate_types = matches.opt_strs("crate-type");
     let (crate_types, emit_metadata) = parse_crate_types_from_list(unparsed_crate_types)
         .unwrap_or_else(|e| early_error(error_format, &e[..]));
@@ -1335,6 +1377,14 @@ pub fn build_session_options_and_crate_config(matches: &getopts::Matches)
         })
     });
 
+    let lint_config_path = matches.opt_str("lint-config").map(PathBuf::from);
+    let lint_config = match lint_config_path {
+        Some(ref path) => {
+            lint::config_file::read(path).unwrap_or_else(|e| early_error(error_format, &e))
+        }
+        None => vec![],
+    };
+
     let debugging_opts = build_debugging_options(matches, error_format);
 
     let mut output_types = BTreeMap::new();
@@ -1547,6 +1597,8 @@ pub fn build_session_options_and_crate_config(matches: &getopts::Matches)
         debuginfo: debuginfo,
         lint_opts: lint_opts,
         lint_cap: lint_cap,
+        lint_config: lint_config,
+        lint_config_path: lint_config_path,
         describe_lints: describe_lints,
         output_types: OutputTypes(output_types),
         search_paths: search_paths,
@@ -1558,6 +1610,8 @@ pub fn build_session_options_and_crate_config(matches: &getopts::Matches)
         prints: prints,
         cg: cg,
         error_format: error_format,
+        error_limit: error_limit,
+        group_diagnostics: group_diagnostics,
         externs: Externs(externs),
         crate_name: crate_name,
         alt_std_name: None,
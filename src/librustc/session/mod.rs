@@ -28,6 +28,7 @@ use syntax::ast::NodeId;
 use errors::{self, DiagnosticBuilder};
 use errors::emitter::{Emitter, EmitterWriter};
 use syntax::json::JsonEmitter;
+use syntax::sarif::SarifEmitter;
 use syntax::feature_gate;
 use syntax::parse;
 use syntax::parse::ParseSess;
@@ -322,7 +323,7 @@ impl Session {
         match self.opts.error_format {
             // when outputting JSON for tool consumption, the tool might want
             // the duplicates
-            config::ErrorOutputType::Json => {
+            config::ErrorOutputType::Json | config::ErrorOutputType::Sarif => {
                 diag_builder.span_note(span, &message);
             },
             _ => {
@@ -538,11 +539,13 @@ pub fn build_session_with_codemap(sopts: config::Options,
     let emitter: Box<Emitter> = match (sopts.error_format, emitter_dest) {
         (config::ErrorOutputType::HumanReadable(color_config), None) => {
             Box::new(EmitterWriter::stderr(color_config,
-                                           Some(codemap.clone())))
+                                           Some(codemap.clone()))
+                         .with_macro_backtrace(sopts.debugging_opts.macro_backtrace))
         }
         (config::ErrorOutputType::HumanReadable(_), Some(dst)) => {
             Box::new(EmitterWriter::new(dst,
-                                        Some(codemap.clone())))
+                                        Some(codemap.clone()))
+                         .with_macro_backtrace(sopts.debugging_opts.macro_backtrace))
         }
         (config::ErrorOutputType::Json, None) => {
             Box::new(JsonEmitter::stderr(Some(registry), codemap.clone()))
@@ -550,12 +553,22 @@ pub fn build_session_with_codemap(sopts: config::Options,
         (config::ErrorOutputType::Json, Some(dst)) => {
             Box::new(JsonEmitter::new(dst, Some(registry), codemap.clone()))
         }
+        (config::ErrorOutputType::Sarif, None) => {
+            Box::new(SarifEmitter::stderr(Some(registry), codemap.clone()))
+        }
+        (config::ErrorOutputType::Sarif, Some(dst)) => {
+            Box::new(SarifEmitter::new(dst, Some(registry), codemap.clone()))
+        }
     };
 
     let diagnostic_handler =
         errors::Handler::with_emitter(can_print_warnings,
                                       treat_err_as_bug,
                                       emitter);
+    if sopts.group_diagnostics {
+        diagnostic_handler.group_diagnostics(codemap.clone());
+    }
+    diagnostic_handler.set_error_limit(sopts.error_limit);
 
     build_session_(sopts,
                    dep_graph,
@@ -723,6 +736,7 @@ pub fn early_error(output: config::ErrorOutputType, msg: &str) -> ! {
                                            None))
         }
         config::ErrorOutputType::Json => Box::new(JsonEmitter::basic()),
+        config::ErrorOutputType::Sarif => Box::new(SarifEmitter::basic()),
     };
     let handler = errors::Handler::with_emitter(true, false, emitter);
     handler.emit(&MultiSpan::new(), msg, errors::Level::Fatal);
@@ -736,6 +750,7 @@ pub fn early_warn(output: config::ErrorOutputType, msg: &str) {
                                            None))
         }
         config::ErrorOutputType::Json => Box::new(JsonEmitter::basic()),
+        config::ErrorOutputType::Sarif => Box::new(SarifEmitter::early()),
     };
     let handler = errors::Handler::with_emitter(true, false, emitter);
     handler.emit(&MultiSpan::new(), msg, errors::Level::Warning);
@@ -978,7 +978,9 @@ pub fn phase_3_run_analysis_passes<'tcx, F, R>(sess: &'tcx Session,
                  || reachable::find_reachable(tcx, &analysis.access_levels));
 
         time(time_passes, "death checking", || {
-            middle::dead::check_crate(tcx, &analysis.access_levels);
+            middle::dead::check_crate(tcx,
+                                      &analysis.access_levels,
+                                      incremental_hashes_map.crate_hash());
         });
 
         time(time_passes, "unused lib feature checking", || {
@@ -0,0 +1,194 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Renders the Markdown of the extended error descriptions for `--explain`
+//! as terminal text: headings are underlined, code blocks indented with the
+//! lines rustdoc hides left out, and, when colors are used, Rust code is
+//! highlighted. Without colors, output that isn't going to a terminal is
+//! printed as Markdown.
+
+use errors;
+use errors::emitter::EmitterWriter;
+
+use std::io;
+use std::iter;
+use std::rc::Rc;
+
+use syntax::codemap::CodeMap;
+use syntax::parse::{self, token};
+use syntax::parse::lexer::StringReader;
+
+const BOLD: &'static str = "\x1b[1m";
+const HEADING: &'static str = "\x1b[1;4m";
+const KEYWORD: &'static str = "\x1b[35m";
+const STRING: &'static str = "\x1b[32m";
+const NUMBER: &'static str = "\x1b[33m";
+const LIFETIME: &'static str = "\x1b[36m";
+const COMMENT: &'static str = "\x1b[90m";
+const RESET: &'static str = "\x1b[0m";
+
+/// Renders an error description, using ANSI escape sequences for colors if
+/// `color` is set.
+pub fn render(description: &str, color: bool) -> String {
+    let mut out = String::new();
+    // The language string and lines of the code block being read, if any.
+    let mut code_block: Option<(bool, Vec<&str>)> = None;
+
+    for line in description.lines() {
+        if line.starts_with("```") {
+            match code_block.take() {
+                Some((is_rust, lines)) => render_code_block(&mut out, &lines, is_rust, color),
+                None => code_block = Some((is_rust_block(&line[3..]), vec![])),
+            }
+            continue;
+        }
+        if let Some((_, ref mut lines)) = code_block {
+            lines.push(line);
+            continue;
+        }
+
+        let level = line.len() - line.trim_left_matches('#').len();
+        if level > 0 && line[level..].starts_with(' ') {
+            let heading = line[level..].trim();
+            if color {
+                out.push_str(&format!("{}{}{}\n", HEADING, heading, RESET));
+            } else {
+                let underline = if level == 1 { '=' } else { '-' };
+                out.push_str(heading);
+                out.push('\n');
+                out.extend(iter::repeat(underline).take(heading.chars().count()));
+                out.push('\n');
+            }
+        } else if color {
+            render_inline_code(&mut out, line);
+            out.push('\n');
+        } else {
+            out.push_str(line);
+            out.push('\n');
+        }
+    }
+    // An unterminated code block.
+    if let Some((is_rust, lines)) = code_block {
+        render_code_block(&mut out, &lines, is_rust, color);
+    }
+    out
+}
+
+/// Whether the language string of a code block makes rustdoc test it as
+/// Rust code, in which case its lines starting with `# ` are hidden.
+fn is_rust_block(lang: &str) -> bool {
+    lang.split(',').map(str::trim).all(|token| {
+        match token {
+            "" | "rust" | "ignore" | "no_run" | "should_panic" | "test_harness" |
+            "compile_fail" => true,
+            _ => token.starts_with('E') && token[1..].chars().all(|c| c.is_digit(10)),
+        }
+    })
+}
+
+fn render_code_block(out: &mut String, lines: &[&str], is_rust: bool, color: bool) {
+    let lines: Vec<&str> = if is_rust {
+        lines.iter().cloned().filter(|line| {
+            let line = line.trim();
+            line != "#" && !line.starts_with("# ")
+        }).collect()
+    } else {
+        lines.to_vec()
+    };
+    let code = lines.join("\n");
+    let code = if is_rust && color {
+        highlight(&code).unwrap_or(code)
+    } else {
+        code
+    };
+    for line in code.lines() {
+        out.push_str("    ");
+        out.push_str(line);
+        out.push('\n');
+    }
+}
+
+/// Shows text between backticks in bold, without the backticks.
+fn render_inline_code(out: &mut String, line: &str) {
+    if line.matches('`').count() % 2 != 0 {
+        out.push_str(line);
+        return;
+    }
+    for (i, part) in line.split('`').enumerate() {
+        if i % 2 == 1 {
+            out.push_str(BOLD);
+            out.push_str(part);
+            out.push_str(RESET);
+        } else {
+            out.push_str(part);
+        }
+    }
+}
+
+/// Highlights Rust code with ANSI escape sequences, or returns `None` if it
+/// can't be lexed.
+fn highlight(code: &str) -> Option<String> {
+    // The lexer reports some errors itself, which should not be shown.
+    let cm = Rc::new(CodeMap::new());
+    let emitter = EmitterWriter::new(Box::new(io::sink()), Some(cm.clone()));
+    let handler = errors::Handler::with_emitter(false, false, Box::new(emitter));
+    let sess = parse::ParseSess::with_span_handler(handler, cm);
+    let fm = sess.codemap().new_filemap("<explain>".to_string(), None, code.to_string());
+    let mut lexer = match StringReader::try_new(&sess, fm) {
+        Ok(lexer) => lexer,
+        Err(errs) => {
+            for mut err in errs {
+                err.cancel();
+            }
+            return None;
+        }
+    };
+
+    let mut out = String::new();
+    loop {
+        let tas = match lexer.try_next_token() {
+            Ok(tas) => tas,
+            Err(_) => {
+                for err in &mut lexer.fatal_errs {
+                    err.cancel();
+                }
+                return None;
+            }
+        };
+        if tas.tok == token::Eof {
+            break;
+        }
+        let text = match sess.codemap().span_to_snippet(tas.sp) {
+            Ok(text) => text,
+            Err(_) => return None,
+        };
+        let style = match tas.tok {
+            token::Comment | token::DocComment(..) => Some(COMMENT),
+            token::Ident(..) if tas.tok.is_any_keyword() => Some(KEYWORD),
+            token::Literal(token::Integer(..), _) |
+            token::Literal(token::Float(..), _) => Some(NUMBER),
+            token::Literal(..) => Some(STRING),
+            token::Lifetime(..) => Some(LIFETIME),
+            _ => None,
+        };
+        match style {
+            // Multi-line comments and strings are styled line by line, so
+            // that the indentation of the block isn't styled.
+            Some(style) => {
+                let lines: Vec<_> = text.split('\n')
+                                        .map(|line| format!("{}{}{}", style, line, RESET))
+                                        .collect();
+                out.push_str(&lines.join("\n"));
+            }
+            None => out.push_str(&text),
+        }
+    }
+    Some(out)
+}
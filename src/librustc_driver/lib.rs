@@ -107,6 +107,7 @@ pub mod driver;
 pub mod pretty;
 pub mod target_features;
 mod derive_registrar;
+mod explain;
 
 const BUG_REPORT_URL: &'static str = "https://github.com/rust-lang/rust/blob/master/CONTRIBUTING.\
                                       md#bug-reports";
@@ -123,6 +124,7 @@ fn abort_msg(err_count: usize) -> String {
 pub fn abort_on_err<T>(result: Result<T, usize>, sess: &Session) -> T {
     match result {
         Err(err_count) => {
+            sess.diagnostic().print_error_summary();
             sess.fatal(&abort_msg(err_count));
         }
         Ok(x) => x,
@@ -134,6 +136,10 @@ pub fn run<F>(run_compiler: F) -> isize
 {
     monitor(move || {
         let (result, session) = run_compiler();
+        if let Some(ref sess) = session {
+            // Emit the diagnostics held back to be grouped, and their summary.
+            sess.diagnostic().print_error_summary();
+        }
         if let Err(err_count) = result {
             if err_count > 0 {
                 match session {
@@ -349,7 +355,8 @@ pub struct RustcDefaultCalls;
 
 fn handle_explain(code: &str,
                   descriptions: &errors::registry::Registry,
-                  output: ErrorOutputType) {
+                  output: ErrorOutputType,
+                  color: bool) {
     let normalised = if code.starts_with("E") {
         code.to_string()
     } else {
@@ -357,14 +364,14 @@ fn handle_explain(code: &str,
     };
     match descriptions.find_description(&normalised) {
         Some(ref description) => {
-            // Slice off the leading newline and print.
-            print!("{}", &(&description[1..]).split("\n").map(|x| {
-                format!("{}\n", if x.starts_with("```") {
-                    "```"
-                } else {
-                    x
-                })
-            }).collect::<String>());
+            // Slice off the leading newline.
+            let description = &description[1..];
+            if color || stdout_isatty() {
+                print!("{}", explain::render(description, color));
+            } else {
+                // Piped output is left as Markdown for other programs to read.
+                print!("{}", description);
+            }
         }
         None => {
             early_error(output, &format!("no extended information for {}", code));
@@ -372,6 +379,52 @@ fn handle_explain(code: &str,
     }
 }
 
+fn handle_explain_search(query: &str,
+                         descriptions: &errors::registry::Registry,
+                         output: ErrorOutputType) {
+    let codes = descriptions.search(query);
+    if codes.is_empty() {
+        early_error(output, &format!("no extended information matches `{}`", query));
+    }
+    for code in codes {
+        let description = descriptions.find_description(code).unwrap();
+        let summary = description.lines().map(str::trim).find(|line| !line.is_empty());
+        println!("{}: {}", code, summary.unwrap_or(""));
+    }
+}
+
+/// Whether to use colors for `--explain`, which prints to stdout.
+fn explain_color(matches: &getopts::Matches) -> bool {
+    match matches.opt_str("color").as_ref().map(|s| &s[..]) {
+        Some("always") => true,
+        Some("never") => false,
+        // The Windows console doesn't understand the escape sequences for colors.
+        _ => !cfg!(windows) && stdout_isatty(),
+    }
+}
+
+#[cfg(unix)]
+fn stdout_isatty() -> bool {
+    unsafe { libc::isatty(libc::STDOUT_FILENO) != 0 }
+}
+
+#[cfg(windows)]
+fn stdout_isatty() -> bool {
+    type DWORD = u32;
+    type BOOL = i32;
+    type HANDLE = *mut u8;
+    const STD_OUTPUT_HANDLE: DWORD = -11i32 as DWORD;
+    extern "system" {
+        fn GetStdHandle(which: DWORD) -> HANDLE;
+        fn GetConsoleMode(hConsoleHandle: HANDLE, lpMode: *mut DWORD) -> BOOL;
+    }
+    unsafe {
+        let handle = GetStdHandle(STD_OUTPUT_HANDLE);
+        let mut out = 0;
+        GetConsoleMode(handle, &mut out) != 0
+    }
+}
+
 impl<'a> CompilerCalls<'a> for RustcDefaultCalls {
     fn early_callback(&mut self,
                       matches: &getopts::Matches,
@@ -381,7 +434,11 @@ impl<'a> CompilerCalls<'a> for RustcDefaultCalls {
                       output: ErrorOutputType)
                       -> Compilation {
         if let Some(ref code) = matches.opt_str("explain") {
-            handle_explain(code, descriptions, output);
+            handle_explain(code, descriptions, output, explain_color(matches));
+            return Compilation::Stop;
+        }
+        if let Some(ref query) = matches.opt_str("explain-search") {
+            handle_explain_search(query, descriptions, output);
             return Compilation::Stop;
         }
 
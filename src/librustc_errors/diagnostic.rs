@@ -166,6 +166,25 @@ impl Diagnostic {
                  Some(Suggestion(CodeSuggestion {
                      msp: sp.into(),
                      substitutes: vec![suggestion],
+                     machine_applicable: false,
+                 })));
+        self
+    }
+
+    /// Like `span_suggestion`, but for suggestions that are certain to be
+    /// correct, so that tools may apply them automatically.
+    pub fn span_suggestion_machine_applicable<S: Into<MultiSpan>>(&mut self,
+                                                                  sp: S,
+                                                                  msg: &str,
+                                                                  suggestion: String)
+                                                                  -> &mut Self {
+        self.sub(Level::Help,
+                 msg,
+                 MultiSpan::new(),
+                 Some(Suggestion(CodeSuggestion {
+                     msp: sp.into(),
+                     substitutes: vec![suggestion],
+                     machine_applicable: true,
                  })));
         self
     }
@@ -95,7 +95,7 @@ impl<'a> DiagnosticBuilder<'a> {
             }
         }
 
-        self.handler.emitter.borrow_mut().emit(&self);
+        self.handler.emit_db(&self);
         self.cancel();
         self.handler.panic_if_treat_err_as_bug();
 
@@ -144,6 +144,11 @@ impl<'a> DiagnosticBuilder<'a> {
                                                         msg: &str,
                                                         suggestion: String)
                                                         -> &mut Self);
+    forward!(pub fn span_suggestion_machine_applicable<S: Into<MultiSpan>>(&mut self,
+                                                                           sp: S,
+                                                                           msg: &str,
+                                                                           suggestion: String)
+                                                                           -> &mut Self);
     forward!(pub fn set_span<S: Into<MultiSpan>>(&mut self, sp: S) -> &mut Self);
     forward!(pub fn code(&mut self, s: String) -> &mut Self);
 
@@ -166,6 +171,15 @@ impl<'a> DiagnosticBuilder<'a> {
         }
     }
 
+    /// Creates a builder to emit an existing diagnostic.
+    pub fn new_diagnostic(handler: &'a Handler, diagnostic: Diagnostic)
+                          -> DiagnosticBuilder<'a> {
+        DiagnosticBuilder {
+            handler: handler,
+            diagnostic: diagnostic,
+        }
+    }
+
     pub fn into_diagnostic(mut self) -> Diagnostic {
         // annoyingly, the Drop impl means we can't actually move
         let result = self.diagnostic.clone();
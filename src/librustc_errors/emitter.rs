@@ -16,6 +16,7 @@ use {Level, CodeSuggestion, DiagnosticBuilder, SubDiagnostic, CodeMapper};
 use RenderSpan::*;
 use snippet::{Annotation, AnnotationType, Line, MultilineAnnotation, StyledString, Style};
 use styled_buffer::StyledBuffer;
+use theme::{self, Theme};
 
 use std::io::prelude::*;
 use std::io;
@@ -33,6 +34,9 @@ impl Emitter for EmitterWriter {
         let mut primary_span = db.span.clone();
         let mut children = db.children.clone();
         self.fix_multispans_in_std_macros(&mut primary_span, &mut children);
+        if let Some(depth) = self.macro_backtrace {
+            self.add_macro_backtrace(&db.span, depth, &mut children);
+        }
         self.emit_messages_default(&db.level,
                                    &db.styled_message(),
                                    &db.code,
@@ -64,6 +68,9 @@ impl ColorConfig {
 pub struct EmitterWriter {
     dst: Destination,
     cm: Option<Rc<CodeMapper>>,
+    /// How many macro expansions to show a snippet of, if any.
+    macro_backtrace: Option<usize>,
+    theme: Theme,
 }
 
 struct FileWithAnnotatedLines {
@@ -94,11 +101,15 @@ impl EmitterWriter {
             EmitterWriter {
                 dst: dst,
                 cm: code_map,
+                macro_backtrace: None,
+                theme: Theme::from_env(),
             }
         } else {
             EmitterWriter {
                 dst: Raw(Box::new(io::stderr())),
                 cm: code_map,
+                macro_backtrace: None,
+                theme: Theme::default(),
             }
         }
     }
@@ -107,9 +118,18 @@ impl EmitterWriter {
         EmitterWriter {
             dst: Raw(dst),
             cm: code_map,
+            macro_backtrace: None,
+            theme: Theme::default(),
         }
     }
 
+    /// Shows a snippet of each of the first `depth` macro expansions a
+    /// diagnostic comes from, if `depth` is `Some`.
+    pub fn with_macro_backtrace(mut self, depth: Option<usize>) -> EmitterWriter {
+        self.macro_backtrace = depth;
+        self
+    }
+
     fn preprocess_annotations(&self, msp: &MultiSpan) -> Vec<FileWithAnnotatedLines> {
         fn add_annotation_to_file(file_vec: &mut Vec<FileWithAnnotatedLines>,
                                   file: Rc<FileMap>,
@@ -636,7 +656,7 @@ impl EmitterWriter {
 
         for sub in children {
             let sub_result = self.get_multispan_max_line_num(&sub.span);
-            max = if sub_result > max { primary } else { max };
+            max = if sub_result > max { sub_result } else { max };
         }
         max
     }
@@ -724,6 +744,88 @@ impl EmitterWriter {
         }
     }
 
+    /// Adds a note for each macro expansion the primary span of a diagnostic
+    /// comes from, innermost first, with a snippet of the code in the macro
+    /// the diagnostic points to and of where the macro was invoked. A macro
+    /// expanding to invocations of itself is shown once, and only the first
+    /// `depth` expansions are shown.
+    fn add_macro_backtrace(&self,
+                           span: &MultiSpan,
+                           depth: usize,
+                           children: &mut Vec<SubDiagnostic>) {
+        let cm = match self.cm {
+            Some(ref cm) => cm,
+            None => return,
+        };
+        let mut sp = match span.primary_span() {
+            Some(sp) if sp != DUMMY_SP && sp != COMMAND_LINE_SP => sp,
+            _ => return,
+        };
+
+        let backtrace = cm.macro_backtrace(sp);
+        let mut i = 0;
+        let mut shown = 0;
+        while i < backtrace.len() {
+            if shown == depth {
+                let message = format!("{} more macro expansions not shown; use \
+                                       `-Z macro-backtrace=N` to show more",
+                                      backtrace.len() - i);
+                children.push(SubDiagnostic {
+                    level: Level::Note,
+                    message: vec![(message, Style::NoStyle)],
+                    span: MultiSpan::new(),
+                    render_span: None,
+                });
+                break;
+            }
+
+            let trace = &backtrace[i];
+            // Desugarings are not macros the user wrote.
+            if trace.macro_decl_name.starts_with("desugaring of ") {
+                sp = trace.call_site;
+                i += 1;
+                continue;
+            }
+            let mut repeats = 0;
+            while backtrace.get(i + repeats + 1).map_or(false, |next| {
+                next.macro_decl_name == trace.macro_decl_name &&
+                next.def_site_span == trace.def_site_span
+            }) {
+                repeats += 1;
+            }
+            let call_site = backtrace[i + repeats].call_site;
+
+            // The code of attribute macros and of the standard macros can't
+            // be shown, only where they were invoked.
+            let show_def = !trace.macro_decl_name.starts_with("#[") &&
+                           sp != DUMMY_SP && sp != COMMAND_LINE_SP &&
+                           !cm.span_to_filename(sp).contains("macros>");
+            let mut msp = if show_def {
+                MultiSpan::from_span(sp)
+            } else {
+                MultiSpan::from_span(call_site)
+            };
+            if show_def && call_site != DUMMY_SP && call_site != COMMAND_LINE_SP {
+                msp.push_span_label(call_site,
+                                    format!("`{}` invoked here", trace.macro_decl_name));
+            }
+            let mut message = format!("in this expansion of `{}`", trace.macro_decl_name);
+            if repeats > 0 {
+                message.push_str(&format!(", and {} recursive expansions of it", repeats));
+            }
+            children.push(SubDiagnostic {
+                level: Level::Note,
+                message: vec![(message, Style::NoStyle)],
+                span: msp,
+                render_span: None,
+            });
+
+            sp = call_site;
+            i += repeats + 1;
+            shown += 1;
+        }
+    }
+
     /// Add a left margin to every line but the first, given a padding length and the label being
     /// displayed, keeping the provided highlighting.
     fn msg_to_buffer(&self,
@@ -824,7 +926,7 @@ impl EmitterWriter {
             match code {
                 &Some(ref code) => {
                     buffer.append(0, "[", Style::Level(level.clone()));
-                    buffer.append(0, &code, Style::Level(level.clone()));
+                    buffer.append(0, &code, Style::LevelCode(level.clone()));
                     buffer.append(0, "]", Style::Level(level.clone()));
                 }
                 _ => {}
@@ -845,12 +947,12 @@ impl EmitterWriter {
             if primary_span != &&DUMMY_SP && primary_span != &&COMMAND_LINE_SP {
                 cm.lookup_char_pos(primary_span.lo)
             } else {
-                emit_to_destination(&buffer.render(), level, &mut self.dst)?;
+                emit_to_destination(&buffer.render(), level, &mut self.dst, &self.theme)?;
                 return Ok(());
             }
         } else {
             // If we don't have span information, emit and exit
-            emit_to_destination(&buffer.render(), level, &mut self.dst)?;
+            emit_to_destination(&buffer.render(), level, &mut self.dst, &self.theme)?;
             return Ok(());
         };
         if let Ok(pos) =
@@ -935,7 +1037,7 @@ impl EmitterWriter {
         }
 
         // final step: take our styled buffer, render it, then output it
-        emit_to_destination(&buffer.render(), level, &mut self.dst)?;
+        emit_to_destination(&buffer.render(), level, &mut self.dst, &self.theme)?;
 
         Ok(())
     }
@@ -980,7 +1082,7 @@ impl EmitterWriter {
             if let Some(_) = lines.next() {
                 buffer.append(row_num, "...", Style::NoStyle);
             }
-            emit_to_destination(&buffer.render(), level, &mut self.dst)?;
+            emit_to_destination(&buffer.render(), level, &mut self.dst, &self.theme)?;
         }
         Ok(())
     }
@@ -998,7 +1100,7 @@ impl EmitterWriter {
                 if !children.is_empty() {
                     let mut buffer = StyledBuffer::new();
                     draw_col_separator_no_space(&mut buffer, 0, max_line_num_len + 1);
-                    match emit_to_destination(&buffer.render(), level, &mut self.dst) {
+                    match emit_to_destination(&buffer.render(), level, &mut self.dst, &self.theme) {
                         Ok(()) => (),
                         Err(e) => panic!("failed to emit error: {}", e)
                     }
@@ -1094,7 +1196,8 @@ fn overlaps(a1: &Annotation, a2: &Annotation) -> bool {
 
 fn emit_to_destination(rendered_buffer: &Vec<Vec<StyledString>>,
                        lvl: &Level,
-                       dst: &mut Destination)
+                       dst: &mut Destination,
+                       theme: &Theme)
                        -> io::Result<()> {
     use lock;
 
@@ -1111,11 +1214,24 @@ fn emit_to_destination(rendered_buffer: &Vec<Vec<StyledString>>,
     // same buffering approach.  Instead, we use a global Windows mutex, which we acquire long
     // enough to output the full error message, then we release.
     let _buffer_lock = lock::acquire_global_lock("rustc_errors");
+    // The escape sequences of hyperlinks can only be written along with
+    // those of colors, not through the Windows console API.
+    let hyperlinks = match *dst {
+        BufferedTerminal(_) => theme.use_hyperlinks(stderr_isatty()),
+        Terminal(_) | Raw(_) => false,
+    };
     for line in rendered_buffer {
         for part in line {
-            dst.apply_style(lvl.clone(), part.style)?;
+            let url = if hyperlinks { hyperlink_url(part) } else { None };
+            if let Some(ref url) = url {
+                write!(dst, "\x1b]8;;{}\x1b\\", url)?;
+            }
+            dst.apply_style(lvl.clone(), part.style, theme)?;
             write!(dst, "{}", part.text)?;
             dst.reset_attrs()?;
+            if url.is_some() {
+                write!(dst, "\x1b]8;;\x1b\\")?;
+            }
         }
         write!(dst, "\n")?;
     }
@@ -1123,6 +1239,28 @@ fn emit_to_destination(rendered_buffer: &Vec<Vec<StyledString>>,
     Ok(())
 }
 
+/// The URL a part of a rendered diagnostic links to: the file of a location
+/// or the explanation of an error code.
+fn hyperlink_url(part: &StyledString) -> Option<String> {
+    match part.style {
+        Style::LineAndColumn => {
+            // Either `file:line:col` or only the file name.
+            let mut file_name = &part.text[..];
+            for _ in 0..2 {
+                match file_name.rfind(':') {
+                    Some(pos) if file_name[pos + 1..].parse::<usize>().is_ok() => {
+                        file_name = &file_name[..pos];
+                    }
+                    _ => break,
+                }
+            }
+            theme::file_url(file_name)
+        }
+        Style::LevelCode(_) => Some(theme::error_code_url(&part.text)),
+        _ => None,
+    }
+}
+
 #[cfg(unix)]
 fn stderr_isatty() -> bool {
     use libc;
@@ -1218,25 +1356,21 @@ impl Destination {
         }
     }
 
-    fn apply_style(&mut self, lvl: Level, style: Style) -> io::Result<()> {
+    fn apply_style(&mut self, lvl: Level, style: Style, theme: &Theme) -> io::Result<()> {
         match style {
             Style::FileNameStyle | Style::LineAndColumn => {}
             Style::LineNumber => {
                 self.start_attr(term::Attr::Bold)?;
-                if cfg!(windows) {
-                    self.start_attr(term::Attr::ForegroundColor(term::color::BRIGHT_CYAN))?;
-                } else {
-                    self.start_attr(term::Attr::ForegroundColor(term::color::BRIGHT_BLUE))?;
-                }
+                self.start_attr(term::Attr::ForegroundColor(theme.line_number))?;
             }
             Style::ErrorCode => {
                 self.start_attr(term::Attr::Bold)?;
-                self.start_attr(term::Attr::ForegroundColor(term::color::BRIGHT_MAGENTA))?;
+                self.start_attr(term::Attr::ForegroundColor(theme.code))?;
             }
             Style::Quotation => {}
             Style::OldSchoolNote => {
                 self.start_attr(term::Attr::Bold)?;
-                self.start_attr(term::Attr::ForegroundColor(term::color::BRIGHT_GREEN))?;
+                self.start_attr(term::Attr::ForegroundColor(theme.note))?;
             }
             Style::OldSchoolNoteText | Style::HeaderMsg => {
                 self.start_attr(term::Attr::Bold)?;
@@ -1246,21 +1380,17 @@ impl Destination {
             }
             Style::UnderlinePrimary | Style::LabelPrimary => {
                 self.start_attr(term::Attr::Bold)?;
-                self.start_attr(term::Attr::ForegroundColor(lvl.color()))?;
+                self.start_attr(term::Attr::ForegroundColor(theme.level_color(lvl)))?;
             }
             Style::UnderlineSecondary |
             Style::LabelSecondary => {
                 self.start_attr(term::Attr::Bold)?;
-                if cfg!(windows) {
-                    self.start_attr(term::Attr::ForegroundColor(term::color::BRIGHT_CYAN))?;
-                } else {
-                    self.start_attr(term::Attr::ForegroundColor(term::color::BRIGHT_BLUE))?;
-                }
+                self.start_attr(term::Attr::ForegroundColor(theme.secondary))?;
             }
             Style::NoStyle => {}
-            Style::Level(l) => {
+            Style::Level(l) | Style::LevelCode(l) => {
                 self.start_attr(term::Attr::Bold)?;
-                self.start_attr(term::Attr::ForegroundColor(l.color()))?;
+                self.start_attr(term::Attr::ForegroundColor(theme.level_color(l)))?;
             }
             Style::Highlight => self.start_attr(term::Attr::Bold)?,
         }
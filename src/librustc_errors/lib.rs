@@ -36,7 +36,8 @@ use self::Level::*;
 use emitter::{Emitter, EmitterWriter};
 
 use std::cell::{RefCell, Cell};
-use std::{error, fmt};
+use std::collections::BTreeMap;
+use std::{error, fmt, mem};
 use std::rc::Rc;
 
 pub mod diagnostic;
@@ -45,6 +46,7 @@ pub mod emitter;
 pub mod snippet;
 pub mod registry;
 pub mod styled_buffer;
+pub mod theme;
 mod lock;
 
 use syntax_pos::{BytePos, Loc, FileLinesResult, FileName, MultiSpan, Span, NO_EXPANSION};
@@ -68,6 +70,9 @@ pub enum RenderSpan {
 pub struct CodeSuggestion {
     pub msp: MultiSpan,
     pub substitutes: Vec<String>,
+    /// Whether the substitutes are certainly what the user wants, so that
+    /// tools may apply them without asking.
+    pub machine_applicable: bool,
 }
 
 pub trait CodeMapper {
@@ -217,6 +222,23 @@ pub struct Handler {
     treat_err_as_bug: bool,
     continue_after_error: Cell<bool>,
     delayed_span_bug: RefCell<Option<(MultiSpan, String)>>,
+
+    // Set by `group_diagnostics`, to find where grouped diagnostics come from.
+    grouping_codemap: RefCell<Option<Rc<CodeMapper>>>,
+    groups: RefCell<Vec<DiagnosticGroup>>,
+    error_limit: Cell<Option<usize>>,
+    // The number of errors reported so far, where a group counts once.
+    reported_errors: Cell<usize>,
+    error_codes: RefCell<BTreeMap<Option<String>, usize>>,
+}
+
+/// Diagnostics with the same level, code, message and primary span, held
+/// back to be reported together.
+struct DiagnosticGroup {
+    diagnostic: Diagnostic,
+    /// The outermost macro call site, if any, of each diagnostic's primary
+    /// span, so that the expansions of one line of a macro are told apart.
+    locations: Vec<Span>,
 }
 
 impl Handler {
@@ -240,6 +262,11 @@ impl Handler {
             treat_err_as_bug: treat_err_as_bug,
             continue_after_error: Cell::new(true),
             delayed_span_bug: RefCell::new(None),
+            grouping_codemap: RefCell::new(None),
+            groups: RefCell::new(Vec::new()),
+            error_limit: Cell::new(None),
+            reported_errors: Cell::new(0),
+            error_codes: RefCell::new(BTreeMap::new()),
         }
     }
 
@@ -247,6 +274,20 @@ impl Handler {
         self.continue_after_error.set(continue_after_error);
     }
 
+    /// Reports errors and warnings with the same code, message and primary
+    /// span, e.g. those from each use of a broken macro, as one diagnostic
+    /// listing all their locations. Grouped diagnostics are held back until
+    /// `flush_grouped` or `print_error_summary` is called, or a fatal error
+    /// is emitted.
+    pub fn group_diagnostics(&self, cm: Rc<CodeMapper>) {
+        *self.grouping_codemap.borrow_mut() = Some(cm);
+    }
+
+    /// Aborts compilation once `limit` errors have been reported.
+    pub fn set_error_limit(&self, limit: Option<usize>) {
+        self.error_limit.set(limit);
+    }
+
     pub fn struct_dummy<'a>(&'a self) -> DiagnosticBuilder<'a> {
         DiagnosticBuilder::new(self, Level::Cancelled, "")
     }
@@ -435,6 +476,7 @@ impl Handler {
         let s;
         match self.err_count.get() {
             0 => {
+                self.flush_grouped();
                 let delayed_bug = self.delayed_span_bug.borrow();
                 match *delayed_bug {
                     Some((ref span, ref errmsg)) => {
@@ -451,6 +493,7 @@ impl Handler {
             }
         }
 
+        self.print_error_summary();
         panic!(self.fatal(&s));
     }
     pub fn emit(&self, msp: &MultiSpan, msg: &str, lvl: Level) {
@@ -475,6 +518,126 @@ impl Handler {
             self.abort_if_errors();
         }
     }
+
+    /// Hands a diagnostic to the emitter, or to the group it belongs to.
+    fn emit_db(&self, db: &DiagnosticBuilder) {
+        let new_error = match db.level {
+            Level::Error | Level::Warning if self.grouping_codemap.borrow().is_some() => {
+                self.add_to_group(db)
+            }
+            Level::Bug | Level::Fatal | Level::PhaseFatal => {
+                self.flush_grouped();
+                self.emitter.borrow_mut().emit(db);
+                false
+            }
+            _ => {
+                self.emitter.borrow_mut().emit(db);
+                true
+            }
+        };
+        if db.level != Level::Error {
+            return;
+        }
+
+        *self.error_codes.borrow_mut().entry(db.code.clone()).or_insert(0) += 1;
+        if new_error {
+            self.reported_errors.set(self.reported_errors.get() + 1);
+            if let Some(limit) = self.error_limit.get() {
+                if self.reported_errors.get() >= limit {
+                    self.print_error_summary();
+                    panic!(self.fatal(&format!("aborting due to `--error-limit {}`", limit)));
+                }
+            }
+        }
+    }
+
+    /// Adds a diagnostic to its group, starting a new group if there is
+    /// none. Returns whether a new group was started.
+    fn add_to_group(&self, db: &DiagnosticBuilder) -> bool {
+        let span = match db.span.primary_span() {
+            Some(span) => span,
+            None => {
+                self.emitter.borrow_mut().emit(db);
+                return true;
+            }
+        };
+        let location = match *self.grouping_codemap.borrow() {
+            Some(ref cm) => cm.macro_backtrace(span).last().map_or(span, |trace| trace.call_site),
+            None => span,
+        };
+
+        let mut groups = self.groups.borrow_mut();
+        for group in groups.iter_mut() {
+            let other = &group.diagnostic;
+            // The expansions of a macro have different spans for the same
+            // code, so only the positions are compared.
+            let same_origin = other.span.primary_span().map_or(false, |other_span| {
+                other_span.lo == span.lo && other_span.hi == span.hi
+            });
+            if same_origin && other.level == db.level && other.code == db.code &&
+               other.message == db.message {
+                if !group.locations.contains(&location) {
+                    group.locations.push(location);
+                }
+                return false;
+            }
+        }
+        groups.push(DiagnosticGroup {
+            diagnostic: (**db).clone(),
+            locations: vec![location],
+        });
+        true
+    }
+
+    /// Emits the diagnostics held back by `group_diagnostics`.
+    pub fn flush_grouped(&self) {
+        let groups = mem::replace(&mut *self.groups.borrow_mut(), Vec::new());
+        let cm = match *self.grouping_codemap.borrow() {
+            Some(ref cm) => cm.clone(),
+            None => return,
+        };
+        for group in groups {
+            let mut db = DiagnosticBuilder::new_diagnostic(self, group.diagnostic);
+            if group.locations.len() > 1 {
+                let mut note = format!("this {} occurred in {} places:",
+                                       db.level, group.locations.len());
+                for &location in &group.locations {
+                    note.push_str("\n    ");
+                    note.push_str(&cm.span_to_string(location));
+                }
+                db.note(&note);
+            }
+            self.emitter.borrow_mut().emit(&db);
+            db.cancel();
+        }
+    }
+
+    /// Emits the held back diagnostics and, when diagnostics are grouped, a
+    /// table of the number of errors with each code. Meant to be called
+    /// once compilation has stopped.
+    pub fn print_error_summary(&self) {
+        self.flush_grouped();
+        if self.grouping_codemap.borrow().is_none() {
+            return;
+        }
+        let counts = mem::replace(&mut *self.error_codes.borrow_mut(), BTreeMap::new());
+        if counts.is_empty() {
+            return;
+        }
+
+        let mut table = "errors by code:".to_string();
+        let width = counts.keys().map(|code| code.as_ref().map_or(7, |c| c.len())).max();
+        // Errors without a code are counted last.
+        let mut rows: Vec<_> = counts.iter().filter(|&(code, _)| code.is_some()).collect();
+        rows.extend(counts.iter().filter(|&(code, _)| code.is_none()));
+        for (code, count) in rows {
+            let code = code.as_ref().map_or("no code", |c| &c[..]);
+            table.push_str(&format!("\n    {:width$}  {}", code, count,
+                                    width = width.unwrap_or(0)));
+        }
+        let mut db = DiagnosticBuilder::new(self, Level::Note, &table);
+        db.emit();
+    }
 }
 
 
@@ -23,4 +23,28 @@ impl Registry {
     pub fn find_description(&self, code: &str) -> Option<&'static str> {
         self.descriptions.get(code).cloned()
     }
+
+    /// Finds the codes whose descriptions contain every word of `query`,
+    /// ignoring case. The codes where the words occur most come first.
+    pub fn search(&self, query: &str) -> Vec<&'static str> {
+        let terms: Vec<String> = query.split_whitespace().map(|t| t.to_lowercase()).collect();
+        if terms.is_empty() {
+            return vec![];
+        }
+
+        let mut found: Vec<_> = self.descriptions.iter().filter_map(|(&code, &desc)| {
+            let text = format!("{}\n{}", code, desc).to_lowercase();
+            let mut score = 0;
+            for term in &terms {
+                match text.matches(&term[..]).count() {
+                    0 => return None,
+                    n => score += n,
+                }
+            }
+            Some((score, code))
+        }).collect();
+        // By decreasing score, then by code.
+        found.sort_by(|a, b| (b.0, a.1).cmp(&(a.0, b.1)));
+        found.into_iter().map(|(_, code)| code).collect()
+    }
 }
@@ -220,5 +220,7 @@ pub enum Style {
     NoStyle,
     ErrorCode,
     Level(Level),
+    // The code of a diagnostic, styled like its level.
+    LevelCode(Level),
     Highlight,
 }
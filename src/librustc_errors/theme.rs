@@ -0,0 +1,273 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The colors of diagnostics printed to a terminal, and whether file names
+//! and error codes in them are hyperlinks.
+//!
+//! Like `GCC_COLORS`, the `RUSTC_COLORS` environment variable changes the
+//! theme with a list of `key=value` entries separated by `:`:
+//!
+//! ```text
+//! RUSTC_COLORS='error=red:warning=bright-yellow:line-number=cyan:hyperlinks=always'
+//! ```
+//!
+//! The keys are the levels `error`, `warning`, `note` and `help`, then
+//! `line-number` for the margin of snippets, `secondary` for the spans and
+//! labels that aren't the primary one and `code` for highlighted error codes.
+//! Their values are `black`, `red`, `green`, `yellow`, `blue`, `magenta`,
+//! `cyan` or `white`, optionally prefixed with `bright-`.
+//!
+//! With `hyperlinks`, file names link to the file and error codes to their
+//! explanation in the error index, using the OSC 8 escape sequence. It is
+//! `auto` by default, which only makes links on terminals known to support
+//! them, but can be set to `always` or `never`. Invalid entries are ignored.
+
+use std::env;
+
+use term::color::{self, Color};
+
+use Level;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Theme {
+    pub error: Color,
+    pub warning: Color,
+    pub note: Color,
+    pub help: Color,
+    pub line_number: Color,
+    pub secondary: Color,
+    pub code: Color,
+    pub hyperlinks: Hyperlinks,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Hyperlinks {
+    Auto,
+    Always,
+    Never,
+}
+
+impl Default for Theme {
+    fn default() -> Theme {
+        let blue = if cfg!(windows) {
+            color::BRIGHT_CYAN
+        } else {
+            color::BRIGHT_BLUE
+        };
+        Theme {
+            error: Level::Error.color(),
+            warning: Level::Warning.color(),
+            note: Level::Note.color(),
+            help: Level::Help.color(),
+            line_number: blue,
+            secondary: blue,
+            code: color::BRIGHT_MAGENTA,
+            hyperlinks: Hyperlinks::Auto,
+        }
+    }
+}
+
+impl Theme {
+    /// The default theme changed by the `RUSTC_COLORS` environment variable.
+    pub fn from_env() -> Theme {
+        match env::var("RUSTC_COLORS") {
+            Ok(spec) => Theme::parse(&spec),
+            Err(_) => Theme::default(),
+        }
+    }
+
+    /// The default theme changed by the entries of `spec`.
+    pub fn parse(spec: &str) -> Theme {
+        let mut theme = Theme::default();
+        for entry in spec.split(':') {
+            let mut parts = entry.splitn(2, '=');
+            let (key, value) = match (parts.next(), parts.next()) {
+                (Some(key), Some(value)) => (key.trim(), value.trim()),
+                _ => continue,
+            };
+            if key == "hyperlinks" {
+                match value {
+                    "auto" => theme.hyperlinks = Hyperlinks::Auto,
+                    "always" => theme.hyperlinks = Hyperlinks::Always,
+                    "never" => theme.hyperlinks = Hyperlinks::Never,
+                    _ => {}
+                }
+                continue;
+            }
+            let color = match parse_color(value) {
+                Some(color) => color,
+                None => continue,
+            };
+            match key {
+                "error" => theme.error = color,
+                "warning" => theme.warning = color,
+                "note" => theme.note = color,
+                "help" => theme.help = color,
+                "line-number" => theme.line_number = color,
+                "secondary" => theme.secondary = color,
+                "code" => theme.code = color,
+                _ => {}
+            }
+        }
+        theme
+    }
+
+    pub fn level_color(&self, lvl: Level) -> Color {
+        match lvl {
+            Level::Bug | Level::Fatal | Level::PhaseFatal | Level::Error => self.error,
+            Level::Warning => self.warning,
+            Level::Note => self.note,
+            Level::Help => self.help,
+            Level::Cancelled => unreachable!(),
+        }
+    }
+
+    /// Whether to make hyperlinks on a terminal on stderr.
+    pub fn use_hyperlinks(&self, isatty: bool) -> bool {
+        match self.hyperlinks {
+            Hyperlinks::Always => true,
+            Hyperlinks::Never => false,
+            Hyperlinks::Auto => isatty && terminal_supports_hyperlinks(),
+        }
+    }
+}
+
+fn parse_color(name: &str) -> Option<Color> {
+    let (bright, name) = if name.starts_with("bright-") {
+        (true, &name["bright-".len()..])
+    } else {
+        (false, name)
+    };
+    let color = match name {
+        "black" => color::BLACK,
+        "red" => color::RED,
+        "green" => color::GREEN,
+        "yellow" => color::YELLOW,
+        "blue" => color::BLUE,
+        "magenta" => color::MAGENTA,
+        "cyan" => color::CYAN,
+        "white" => color::WHITE,
+        _ => return None,
+    };
+    Some(if bright { color + color::BRIGHT_BLACK } else { color })
+}
+
+/// Guesses from the environment whether the terminal shows OSC 8 hyperlinks.
+/// Others may print the escape sequences, so only the terminals known to
+/// support them are trusted.
+fn terminal_supports_hyperlinks() -> bool {
+    if env::var("TERM").map(|term| term == "dumb").unwrap_or(false) {
+        return false;
+    }
+    // VTE based terminals, e.g. GNOME Terminal, support them since 0.50.
+    if let Ok(version) = env::var("VTE_VERSION") {
+        if version.parse::<u32>().map(|v| v >= 5000).unwrap_or(false) {
+            return true;
+        }
+    }
+    if let Ok(program) = env::var("TERM_PROGRAM") {
+        if program == "iTerm.app" || program == "WezTerm" || program == "vscode" {
+            return true;
+        }
+    }
+    env::var("KITTY_WINDOW_ID").is_ok()
+}
+
+/// The URL of a file name from a diagnostic, if it names a file on disk.
+pub fn file_url(file_name: &str) -> Option<String> {
+    // E.g. `<std macros>` or `<anon>`.
+    if file_name.starts_with('<') {
+        return None;
+    }
+    let path = match env::current_dir() {
+        Ok(dir) => dir.join(file_name),
+        Err(_) => return None,
+    };
+    let mut url = "file://".to_string();
+    let path = path.to_string_lossy();
+    if !path.starts_with('/') {
+        // A Windows path, e.g. `C:\src\lib.rs`.
+        url.push('/');
+    }
+    for byte in path.bytes() {
+        match byte {
+            b'a'...b'z' | b'A'...b'Z' | b'0'...b'9' | b'-' | b'.' | b'_' | b'~' | b'/' |
+            b':' => url.push(byte as char),
+            b'\\' => url.push('/'),
+            _ => url.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    Some(url)
+}
+
+/// The URL of the explanation of an error code.
+pub fn error_code_url(code: &str) -> String {
+    format!("https://doc.rust-lang.org/error-index.html#{}", code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Hyperlinks, Theme, file_url, parse_color};
+    use term::color;
+
+    #[test]
+    fn parse_valid_spec() {
+        let theme = Theme::parse("error=green:warning=bright-yellow: line-number = cyan :\
+                                  hyperlinks=always");
+        assert_eq!(theme.error, color::GREEN);
+        assert_eq!(theme.warning, color::BRIGHT_YELLOW);
+        assert_eq!(theme.line_number, color::CYAN);
+        assert_eq!(theme.hyperlinks, Hyperlinks::Always);
+        assert_eq!(theme.note, Theme::default().note);
+        assert_eq!(theme.code, Theme::default().code);
+    }
+
+    #[test]
+    fn parse_invalid_spec() {
+        assert_eq!(Theme::parse(""), Theme::default());
+        assert_eq!(Theme::parse("error"), Theme::default());
+        assert_eq!(Theme::parse("::="), Theme::default());
+        assert_eq!(Theme::parse("error=:help=red=blue"), Theme::default());
+        assert_eq!(Theme::parse("hyperlinks=sometimes"), Theme::default());
+
+        // A wrong entry doesn't keep the others from being used.
+        let theme = Theme::parse("frobnicate=red:help=magenta:note");
+        assert_eq!(theme.help, color::MAGENTA);
+        assert_eq!(theme.note, Theme::default().note);
+    }
+
+    #[test]
+    fn parse_unknown_colors() {
+        assert_eq!(parse_color("red"), Some(color::RED));
+        assert_eq!(parse_color("bright-white"), Some(color::BRIGHT_WHITE));
+        assert_eq!(parse_color("orange"), None);
+        assert_eq!(parse_color("bright-"), None);
+        assert_eq!(parse_color("bright-bright-red"), None);
+        assert_eq!(parse_color("Red"), None);
+        assert_eq!(Theme::parse("error=orange"), Theme::default());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn file_url_escaping() {
+        assert_eq!(file_url("<std macros>"), None);
+        assert_eq!(file_url("/src/lib.rs"), Some("file:///src/lib.rs".to_string()));
+        assert_eq!(file_url("/src/a b%#?.rs"),
+                   Some("file:///src/a%20b%25%23%3F.rs".to_string()));
+        assert_eq!(file_url("/src/é.rs"), Some("file:///src/%C3%A9.rs".to_string()));
+    }
+
+    #[test]
+    fn file_url_relative() {
+        let url = file_url("src/lib.rs").unwrap();
+        assert!(url.starts_with("file:///"), "{}", url);
+        assert!(url.ends_with("/src/lib.rs"), "{}", url);
+    }
+}
@@ -33,6 +33,7 @@ use std::hash::Hash;
 use rustc::dep_graph::DepNode;
 use rustc::hir;
 use rustc::hir::def_id::{CRATE_DEF_INDEX, DefId};
+use rustc::hir::svh::Svh;
 use rustc::hir::intravisit as visit;
 use rustc::hir::intravisit::{Visitor, NestedVisitorMap};
 use rustc::ty::TyCtxt;
@@ -83,6 +84,11 @@ impl IncrementalHashesMap {
     pub fn len(&self) -> usize {
         self.hashes.len()
     }
+
+    /// The hash of the whole crate, which identifies this build of it.
+    pub fn crate_hash(&self) -> Svh {
+        Svh::new(self[&DepNode::Krate].to_smaller_hash())
+    }
 }
 
 impl<'a> ::std::ops::Index<&'a DepNode<DefId>> for IncrementalHashesMap {
@@ -47,7 +47,7 @@ use syntax::feature_gate::{AttributeGate, AttributeType, Stability, deprecated_a
 use syntax_pos::Span;
 
 use rustc::hir::{self, PatKind};
-use rustc::hir::intravisit::FnKind;
+use rustc::hir::intravisit::{self, Visitor, FnKind, NestedVisitorMap};
 
 use bad_style::{MethodLateContext, method_context};
 
@@ -257,6 +257,18 @@ declare_lint! {
     "detects missing documentation for public members"
 }
 
+declare_lint! {
+    MISSING_ERRORS_DOC,
+    Allow,
+    "detects documented public functions returning `Result` without an `# Errors` section"
+}
+
+declare_lint! {
+    MISSING_PANICS_DOC,
+    Allow,
+    "detects documented public functions which may panic without a `# Panics` section"
+}
+
 pub struct MissingDoc {
     /// Stack of IDs of struct definitions.
     struct_def_stack: Vec<ast::NodeId>,
@@ -319,11 +331,175 @@ impl MissingDoc {
                          &format!("missing documentation for {}", desc));
         }
     }
+
+    /// Checks that the docs of a public function have the sections its
+    /// signature and body call for. Undocumented functions are left to
+    /// `missing_docs`.
+    fn check_missing_doc_sections<'a, 'tcx>(&self,
+                                            cx: &LateContext<'a, 'tcx>,
+                                            id: ast::NodeId,
+                                            attrs: &[ast::Attribute],
+                                            body: &'tcx hir::Body,
+                                            sp: Span) {
+        if cx.sess().opts.test || self.doc_hidden() || !cx.access_levels.is_exported(id) {
+            return;
+        }
+
+        let docs = attrs.iter()
+                        .filter(|a| a.is_value_str() && a.name() == "doc")
+                        .filter_map(|a| a.value_str())
+                        .collect::<Vec<_>>();
+        if docs.is_empty() {
+            return;
+        }
+        // Sections are Markdown headers of any level, e.g. `# Errors`.
+        let has_section = |name: &str| {
+            docs.iter().any(|doc| {
+                doc.as_str().lines().any(|line| {
+                    let line = line.trim();
+                    line.starts_with('#') && line.trim_left_matches('#').trim() == name
+                })
+            })
+        };
+
+        let fn_ty = cx.tcx.item_type(cx.tcx.hir.local_def_id(id));
+        let output = *fn_ty.fn_sig().output().skip_binder();
+        if is_std_type(cx, output, "result::Result") && !has_section("Errors") {
+            cx.span_lint(MISSING_ERRORS_DOC,
+                         sp,
+                         "docs for function returning `Result` missing `# Errors` section");
+        }
+
+        if !has_section("Panics") {
+            let mut finder = PanicFinder {
+                cx: cx,
+                panic_span: None,
+            };
+            finder.visit_body(body);
+            if let Some(panic_span) = finder.panic_span {
+                cx.span_lint_note(MISSING_PANICS_DOC,
+                                  sp,
+                                  "docs for function which may panic missing `# Panics` section",
+                                  panic_span,
+                                  "first possible panic found here");
+            }
+        }
+    }
+}
+
+/// Whether `ty` is the standard library type at `path`, e.g.
+/// `"option::Option"`, be it named through `std` or `core`.
+fn is_std_type(cx: &LateContext, ty: Ty, path: &str) -> bool {
+    match ty.sty {
+        ty::TyAdt(def, _) => {
+            let item_path = cx.tcx.item_path_str(def.did);
+            item_path == format!("std::{}", path) || item_path == format!("core::{}", path)
+        }
+        _ => false,
+    }
+}
+
+/// If `e` calls `unwrap` or `expect` of `Option` or `Result`, the path of
+/// the method, e.g. `"Option::unwrap"`. The method is looked up in the
+/// typeck tables and compared with the lang items of these methods, so that
+/// methods with the same name on other types or from traits are told apart.
+fn std_unwrap_call(cx: &LateContext, e: &hir::Expr) -> Option<&'static str> {
+    let def_id = match e.node {
+        hir::ExprMethodCall(..) => {
+            match cx.tables.method_map.get(&ty::MethodCall::expr(e.id)) {
+                Some(method) => method.def_id,
+                None => return None,
+            }
+        }
+        // E.g. `Option::unwrap(x)`.
+        hir::ExprCall(ref callee, _) => {
+            match callee.node {
+                hir::ExprPath(ref qpath) => {
+                    match cx.tables.qpath_def(qpath, callee.id) {
+                        Def::Method(def_id) => def_id,
+                        _ => return None,
+                    }
+                }
+                _ => return None,
+            }
+        }
+        _ => return None,
+    };
+
+    let lang_items = &cx.tcx.lang_items;
+    let def_id = Some(def_id);
+    if def_id == lang_items.option_unwrap_fn() {
+        Some("Option::unwrap")
+    } else if def_id == lang_items.option_expect_fn() {
+        Some("Option::expect")
+    } else if def_id == lang_items.result_unwrap_fn() {
+        Some("Result::unwrap")
+    } else if def_id == lang_items.result_expect_fn() {
+        Some("Result::expect")
+    } else {
+        None
+    }
+}
+
+/// Finds the first expression of a function body which visibly panics: a
+/// `panic!` or a macro built on it like `assert!`, a call to `unwrap` or
+/// `expect` on an `Option` or a `Result`, or indexing.
+struct PanicFinder<'a, 'tcx: 'a> {
+    cx: &'a LateContext<'a, 'tcx>,
+    panic_span: Option<Span>,
+}
+
+impl<'a, 'tcx> PanicFinder<'a, 'tcx> {
+    fn may_panic(&self, e: &hir::Expr) -> bool {
+        match e.node {
+            hir::ExprCall(ref callee, _) => {
+                let def = match callee.node {
+                    hir::ExprPath(ref qpath) => self.cx.tables.qpath_def(qpath, callee.id),
+                    _ => return false,
+                };
+                match def {
+                    Def::Fn(def_id) => {
+                        let name = self.cx.tcx.item_name(def_id);
+                        match &*self.cx.tcx.crate_name(def_id.krate).as_str() {
+                            "std" => name == "begin_panic" || name == "begin_panic_fmt",
+                            "core" => name == "panic" || name == "panic_fmt",
+                            _ => false,
+                        }
+                    }
+                    _ => false,
+                }
+            }
+            hir::ExprMethodCall(..) => std_unwrap_call(self.cx, e).is_some(),
+            // `v[..]` can't go out of bounds.
+            hir::ExprIndex(_, ref index) => {
+                !is_std_type(self.cx, self.cx.tables.expr_ty(index), "ops::RangeFull")
+            }
+            _ => false,
+        }
+    }
+}
+
+impl<'a, 'tcx> Visitor<'tcx> for PanicFinder<'a, 'tcx> {
+    fn nested_visit_map<'this>(&'this mut self) -> NestedVisitorMap<'this, 'tcx> {
+        NestedVisitorMap::OnlyBodies(&self.cx.tcx.hir)
+    }
+
+    fn visit_expr(&mut self, e: &'tcx hir::Expr) {
+        if self.panic_span.is_some() {
+            return;
+        }
+        if self.may_panic(e) {
+            // Point at the `panic!` or `assert!` rather than into its expansion.
+            self.panic_span = Some(self.cx.sess().codemap().source_callsite(e.span));
+            return;
+        }
+        intravisit::walk_expr(self, e);
+    }
 }
 
 impl LintPass for MissingDoc {
     fn get_lints(&self) -> LintArray {
-        lint_array!(MISSING_DOCS)
+        lint_array!(MISSING_DOCS, MISSING_ERRORS_DOC, MISSING_PANICS_DOC)
     }
 }
 
@@ -448,6 +624,27 @@ impl<'a, 'tcx> LateLintPass<'a, 'tcx> for MissingDoc {
                                       desc);
     }
 
+    fn check_fn(&mut self,
+                cx: &LateContext<'a, 'tcx>,
+                fn_kind: FnKind<'tcx>,
+                _: &'tcx hir::FnDecl,
+                body: &'tcx hir::Body,
+                sp: Span,
+                id: ast::NodeId) {
+        let attrs = match fn_kind {
+            FnKind::ItemFn(.., attrs) => attrs,
+            FnKind::Method(.., attrs) => {
+                // Methods of trait impls are documented by the trait.
+                if method_context(cx, id, sp) == MethodLateContext::TraitImpl {
+                    return;
+                }
+                attrs
+            }
+            FnKind::Closure(_) => return,
+        };
+        self.check_missing_doc_sections(cx, id, attrs, body, sp);
+    }
+
     fn check_struct_field(&mut self, cx: &LateContext, sf: &hir::StructField) {
         if !sf.is_positional() {
             if sf.vis == hir::Public || self.in_variant {
@@ -479,6 +676,72 @@ impl<'a, 'tcx> LateLintPass<'a, 'tcx> for MissingDoc {
     }
 }
 
+declare_lint! {
+    pub UNWRAP_USED,
+    Allow,
+    "calls to `unwrap` or `expect` on an `Option` or a `Result` outside of tests"
+}
+
+/// Checks for `unwrap` and `expect` calls which may panic, except in
+/// `#[test]` functions and `#[cfg(test)]` items.
+pub struct UnwrapUsed {
+    /// Whether we are in test code, at each level which has lint attributes.
+    in_test_stack: Vec<bool>,
+}
+
+impl UnwrapUsed {
+    pub fn new() -> UnwrapUsed {
+        UnwrapUsed { in_test_stack: vec![false] }
+    }
+
+    fn in_test(&self) -> bool {
+        *self.in_test_stack.last().expect("empty in_test_stack")
+    }
+}
+
+impl LintPass for UnwrapUsed {
+    fn get_lints(&self) -> LintArray {
+        lint_array!(UNWRAP_USED)
+    }
+}
+
+impl<'a, 'tcx> LateLintPass<'a, 'tcx> for UnwrapUsed {
+    fn enter_lint_attrs(&mut self, _: &LateContext, attrs: &[ast::Attribute]) {
+        let in_test = self.in_test() ||
+                      attrs.iter().any(|attr| {
+            attr.check_name("test") ||
+            (attr.check_name("cfg") &&
+             match attr.meta_item_list() {
+                 None => false,
+                 Some(l) => attr::list_contains_name(&l[..], "test"),
+             })
+        });
+        self.in_test_stack.push(in_test);
+    }
+
+    fn exit_lint_attrs(&mut self, _: &LateContext, _attrs: &[ast::Attribute]) {
+        self.in_test_stack.pop().expect("empty in_test_stack");
+    }
+
+    fn check_expr(&mut self, cx: &LateContext, e: &hir::Expr) {
+        if self.in_test() {
+            return;
+        }
+        if let Some(method) = std_unwrap_call(cx, e) {
+            let span = cx.sess().codemap().source_callsite(e.span);
+            let mut err = cx.struct_span_lint(UNWRAP_USED,
+                                              span,
+                                              &format!("used `{}`, which may panic", method));
+            if method.starts_with("Option") {
+                err.help("handle the `None` case, e.g. with `match`, `if let` or `ok_or`");
+            } else {
+                err.help("handle the error, e.g. with `match` or `?`");
+            }
+            err.emit();
+        }
+    }
+}
+
 declare_lint! {
     pub MISSING_COPY_IMPLEMENTATIONS,
     Allow,
@@ -0,0 +1,194 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Lints on the size and complexity of functions.
+//!
+//! The limits default to `DEFAULT_COMPLEXITY_LIMIT` and
+//! `DEFAULT_STATEMENT_LIMIT`, and can be changed for a crate with
+//! `#![cyclomatic_complexity_limit = "N"]` and `#![fn_statement_limit = "N"]`,
+//! which are unstable behind `#![feature(fn_complexity_limits)]`.
+
+use lint::{LateContext, LintContext, LintArray};
+use lint::{LintPass, LateLintPass};
+
+use syntax::ast;
+use syntax_pos::{Span, NO_EXPANSION};
+
+use rustc::hir::{self, intravisit};
+use rustc::hir::intravisit::{FnKind, NestedVisitorMap, Visitor};
+use rustc::hir::map as hir_map;
+
+declare_lint! {
+    pub CYCLOMATIC_COMPLEXITY,
+    Allow,
+    "functions whose cyclomatic complexity exceeds `#![cyclomatic_complexity_limit]`"
+}
+
+declare_lint! {
+    pub TOO_MANY_STATEMENTS,
+    Allow,
+    "functions with more statements than `#![fn_statement_limit]`"
+}
+
+pub const DEFAULT_COMPLEXITY_LIMIT: usize = 25;
+pub const DEFAULT_STATEMENT_LIMIT: usize = 50;
+
+pub struct FnComplexity {
+    complexity_limit: usize,
+    statement_limit: usize,
+}
+
+impl FnComplexity {
+    pub fn new() -> FnComplexity {
+        FnComplexity {
+            complexity_limit: DEFAULT_COMPLEXITY_LIMIT,
+            statement_limit: DEFAULT_STATEMENT_LIMIT,
+        }
+    }
+}
+
+impl LintPass for FnComplexity {
+    fn get_lints(&self) -> LintArray {
+        lint_array!(CYCLOMATIC_COMPLEXITY, TOO_MANY_STATEMENTS)
+    }
+}
+
+fn read_limit(cx: &LateContext, attrs: &[ast::Attribute], name: &str, limit: &mut usize) {
+    for attr in attrs {
+        if !attr.check_name(name) {
+            continue;
+        }
+
+        if let Some(n) = attr.value_str().and_then(|s| s.as_str().parse().ok()) {
+            *limit = n;
+            return;
+        }
+
+        cx.sess().span_err(attr.span,
+                           &format!("malformed {} attribute, expected #![{}=\"N\"]",
+                                    name, name));
+    }
+}
+
+impl<'a, 'tcx> LateLintPass<'a, 'tcx> for FnComplexity {
+    fn check_crate(&mut self, cx: &LateContext, krate: &hir::Crate) {
+        read_limit(cx, &krate.attrs, "cyclomatic_complexity_limit", &mut self.complexity_limit);
+        read_limit(cx, &krate.attrs, "fn_statement_limit", &mut self.statement_limit);
+    }
+
+    fn check_fn(&mut self,
+                cx: &LateContext<'a, 'tcx>,
+                fn_kind: FnKind<'tcx>,
+                _: &'tcx hir::FnDecl,
+                body: &'tcx hir::Body,
+                sp: Span,
+                _: ast::NodeId) {
+        // Closures are counted as part of the function they are in, and
+        // generated functions, e.g. from `#[derive]`, can't be split up.
+        if let FnKind::Closure(_) = fn_kind {
+            return;
+        }
+        if sp.expn_id != NO_EXPANSION {
+            return;
+        }
+
+        let mut counter = ComplexityCounter {
+            hir_map: &cx.tcx.hir,
+            complexity: 1,
+            statements: 0,
+        };
+        counter.visit_body(body);
+
+        if counter.complexity > self.complexity_limit {
+            let msg = format!("function has a cyclomatic complexity of {}, which exceeds the \
+                               limit of {}",
+                              counter.complexity, self.complexity_limit);
+            let mut err = cx.struct_span_lint(CYCLOMATIC_COMPLEXITY, sp, &msg);
+            err.help("consider splitting it into smaller functions");
+            err.note("the limit can be changed with `#![cyclomatic_complexity_limit = \"N\"]`");
+            err.emit();
+        }
+        if counter.statements > self.statement_limit {
+            let msg = format!("function has {} statements, which exceeds the limit of {}",
+                              counter.statements, self.statement_limit);
+            let mut err = cx.struct_span_lint(TOO_MANY_STATEMENTS, sp, &msg);
+            err.help("consider splitting it into smaller functions");
+            err.note("the limit can be changed with `#![fn_statement_limit = \"N\"]`");
+            err.emit();
+        }
+    }
+}
+
+/// Computes the cyclomatic complexity of a function body, one plus the number
+/// of decision points in it, and counts its statements.
+///
+/// Each `if`, loop, match guard and match arm but the first is a decision
+/// point. `if let`, `while let`, `for` and `?` are matches in the HIR, so they
+/// are counted through their arms like `try!`.
+struct ComplexityCounter<'a, 'tcx: 'a> {
+    hir_map: &'a hir_map::Map<'tcx>,
+    complexity: usize,
+    statements: usize,
+}
+
+impl<'a, 'tcx> Visitor<'tcx> for ComplexityCounter<'a, 'tcx> {
+    fn nested_visit_map<'this>(&'this mut self) -> NestedVisitorMap<'this, 'tcx> {
+        NestedVisitorMap::OnlyBodies(self.hir_map)
+    }
+
+    fn visit_expr(&mut self, e: &'tcx hir::Expr) {
+        match e.node {
+            hir::ExprIf(..) |
+            hir::ExprWhile(..) |
+            hir::ExprLoop(_, _, hir::LoopSource::Loop) => self.complexity += 1,
+            hir::ExprMatch(_, ref arms, _) => {
+                self.complexity += arms.len().saturating_sub(1);
+                self.complexity += arms.iter().filter(|arm| arm.guard.is_some()).count();
+            }
+            _ => {}
+        }
+        intravisit::walk_expr(self, e);
+    }
+
+    fn visit_stmt(&mut self, s: &'tcx hir::Stmt) {
+        match s.node {
+            // Items are not part of the function's code.
+            hir::StmtDecl(ref decl, _) if is_item(decl) => {}
+            // The `let` binding the result of a desugared `for` loop.
+            hir::StmtDecl(ref decl, _) if is_for_loop_result(decl) => {}
+            _ => self.statements += 1,
+        }
+        intravisit::walk_stmt(self, s);
+    }
+}
+
+fn is_item(decl: &hir::Decl) -> bool {
+    match decl.node {
+        hir::DeclItem(_) => true,
+        hir::DeclLocal(_) => false,
+    }
+}
+
+fn is_for_loop_result(decl: &hir::Decl) -> bool {
+    match decl.node {
+        hir::DeclLocal(ref local) => {
+            match local.init {
+                Some(ref init) => {
+                    match init.node {
+                        hir::ExprMatch(_, _, hir::MatchSource::ForLoopDesugar) => true,
+                        _ => false,
+                    }
+                }
+                None => false,
+            }
+        }
+        hir::DeclItem(_) => false,
+    }
+}
@@ -58,11 +58,13 @@ use lint::FutureIncompatibleInfo;
 
 mod bad_style;
 mod builtin;
+mod complexity;
 mod types;
 mod unused;
 
 use bad_style::*;
 use builtin::*;
+use complexity::*;
 use types::*;
 use unused::*;
 
@@ -147,6 +149,8 @@ pub fn register_builtins(store: &mut lint::LintStore, sess: Option<&Session>) {
                           TypeLimits,
                           MissingDoc,
                           MissingDebugImplementations,
+                          FnComplexity,
+                          UnwrapUsed,
                           );
 
     add_lint_group!(sess,
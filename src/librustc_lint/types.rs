@@ -51,6 +51,24 @@ declare_lint! {
     "shift exceeds the type's number of bits"
 }
 
+declare_lint! {
+    LOSSY_INTEGER_CAST,
+    Allow,
+    "casts to a narrower integer type, which may truncate the value"
+}
+
+declare_lint! {
+    SIGN_CHANGING_CAST,
+    Allow,
+    "casts between signed and unsigned integer types, which may change the sign of the value"
+}
+
+declare_lint! {
+    FLOAT_TO_INT_CAST,
+    Allow,
+    "casts from floating-point to integer types"
+}
+
 declare_lint! {
     VARIANT_SIZE_DIFFERENCES,
     Allow,
@@ -73,7 +91,10 @@ impl LintPass for TypeLimits {
     fn get_lints(&self) -> LintArray {
         lint_array!(UNUSED_COMPARISONS,
                     OVERFLOWING_LITERALS,
-                    EXCEEDING_BITSHIFTS)
+                    EXCEEDING_BITSHIFTS,
+                    LOSSY_INTEGER_CAST,
+                    SIGN_CHANGING_CAST,
+                    FLOAT_TO_INT_CAST)
     }
 }
 
@@ -195,6 +216,7 @@ impl<'a, 'tcx> LateLintPass<'a, 'tcx> for TypeLimits {
                     _ => (),
                 };
             }
+            hir::ExprCast(ref expr, _) => check_cast(cx, e, &expr),
             _ => (),
         };
 
@@ -318,6 +340,132 @@ impl<'a, 'tcx> LateLintPass<'a, 'tcx> for TypeLimits {
             }
         }
 
+        // The signedness and number of bits of an integer type on the target.
+        fn int_ty_sign_bits(cx: &LateContext, ty: Ty) -> Option<(bool, u64)> {
+            match ty.sty {
+                ty::TyInt(t) => Some((true, int_ty_bits(t, cx.sess().target.int_type))),
+                ty::TyUint(t) => Some((false, uint_ty_bits(t, cx.sess().target.uint_type))),
+                _ => None,
+            }
+        }
+
+        // Whether the value of `expr` is known to fit into `to_ty`, because it
+        // is a constant or it is masked with one.
+        fn cast_value_fits(cx: &LateContext, expr: &hir::Expr, to_ty: Ty) -> bool {
+            let const_cx = ConstContext::with_tables(cx.tcx, cx.tables);
+            let const_fits = |e: &hir::Expr, allow_negative: bool| {
+                let v = match const_cx.eval(e, ExprTypeChecked) {
+                    Ok(ConstVal::Integral(v)) => v,
+                    _ => return false,
+                };
+                match to_ty.sty {
+                    ty::TyInt(t) => {
+                        let t = if let ast::IntTy::Is = t {
+                            cx.sess().target.int_type
+                        } else {
+                            t
+                        };
+                        let (min, max) = int_ty_range(t);
+                        if v.is_negative() {
+                            allow_negative && v.to_u128_unchecked() as i128 >= min
+                        } else {
+                            v.to_u128().map_or(false, |v| v <= max as u128)
+                        }
+                    }
+                    ty::TyUint(t) => {
+                        let t = if let ast::UintTy::Us = t {
+                            cx.sess().target.uint_type
+                        } else {
+                            t
+                        };
+                        let (_, max) = uint_ty_range(t);
+                        v.to_u128().map_or(false, |v| v <= max)
+                    }
+                    _ => false,
+                }
+            };
+            match expr.node {
+                hir::ExprBinary(binop, ref l, ref r) if binop.node == hir::BiBitAnd => {
+                    const_fits(&l, false) || const_fits(&r, false)
+                }
+                _ => const_fits(expr, true),
+            }
+        }
+
+        fn check_cast(cx: &LateContext, e: &hir::Expr, expr: &hir::Expr) {
+            let from_ty = cx.tables.node_id_to_type(expr.id);
+            let to_ty = cx.tables.node_id_to_type(e.id);
+
+            if let ty::TyFloat(_) = from_ty.sty {
+                if int_ty_sign_bits(cx, to_ty).is_some() {
+                    let msg = format!("casting `{}` to `{}` truncates the value, and is \
+                                       undefined behavior if it does not fit",
+                                      from_ty, to_ty);
+                    let mut err = cx.struct_span_lint(FLOAT_TO_INT_CAST, e.span, &msg);
+                    err.help(&format!("round the value explicitly with `round`, `floor` or \
+                                       `ceil`, and check it against `{0}::min_value()` and \
+                                       `{0}::max_value()` first",
+                                      to_ty));
+                    err.emit();
+                }
+                return;
+            }
+
+            let ((from_signed, from_bits), (to_signed, to_bits)) =
+                match (int_ty_sign_bits(cx, from_ty), int_ty_sign_bits(cx, to_ty)) {
+                    (Some(from), Some(to)) => (from, to),
+                    _ => return,
+                };
+            let truncates = to_bits < from_bits;
+            let changes_sign = from_signed != to_signed &&
+                               (from_signed || to_bits == from_bits);
+            if !(truncates || changes_sign) || cast_value_fits(cx, expr, to_ty) {
+                return;
+            }
+
+            let limits = if to_signed {
+                format!("`{0}::min_value()` and `{0}::max_value()`", to_ty)
+            } else {
+                format!("`{}::max_value()`", to_ty)
+            };
+            if truncates {
+                let msg = format!("casting `{}` to `{}` may truncate the value", from_ty, to_ty);
+                let mut err = cx.struct_span_lint(LOSSY_INTEGER_CAST, e.span, &msg);
+                if !to_signed {
+                    if let Ok(snippet) = cx.sess().codemap().span_to_snippet(expr.span) {
+                        let snippet = match expr.node {
+                            hir::ExprBinary(..) => format!("({})", snippet),
+                            _ => snippet,
+                        };
+                        let mask = (1u128 << to_bits) - 1;
+                        err.span_suggestion(e.span,
+                                            "if the truncation is intended, mask the value \
+                                             explicitly",
+                                            format!("({} & {:#x}) as {}", snippet, mask, to_ty));
+                    }
+                }
+                err.help(&format!("otherwise use a checked conversion such as `TryFrom`, or \
+                                   check the value against {} first",
+                                  limits));
+                err.emit();
+            } else {
+                let msg = format!("casting `{}` to `{}` may change the sign of the value",
+                                  from_ty, to_ty);
+                let mut err = cx.struct_span_lint(SIGN_CHANGING_CAST, e.span, &msg);
+                if from_signed {
+                    err.note("negative values wrap around to large positive ones");
+                } else {
+                    err.note(&format!("values above `{}::max_value()` wrap around to \
+                                       negative ones",
+                                      to_ty));
+                }
+                err.help(&format!("use a checked conversion such as `TryFrom`, or check the \
+                                   value against {} first",
+                                  limits));
+                err.emit();
+            }
+        }
+
         fn is_comparison(binop: hir::BinOp) -> bool {
             match binop.node {
                 hir::BiEq | hir::BiLt | hir::BiLe | hir::BiNe | hir::BiGe | hir::BiGt => true,
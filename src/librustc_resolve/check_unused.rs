@@ -19,6 +19,7 @@
 // Unused trait imports can't be checked until the method resolution. We save
 // candidates here, and do the acutal check in librustc_typeck/check_unused.rs.
 
+use std::cmp;
 use std::ops::{Deref, DerefMut};
 
 use Resolver;
@@ -26,6 +27,7 @@ use resolve_imports::ImportDirectiveSubclass;
 
 use rustc::{lint, ty};
 use rustc::util::nodemap::NodeMap;
+use errors::{Diagnostic, Level};
 use syntax::ast::{self, ViewPathGlob, ViewPathList, ViewPathSimple};
 use syntax::visit::{self, Visitor};
 use syntax_pos::{Span, MultiSpan, DUMMY_SP};
@@ -35,6 +37,9 @@ struct UnusedImportCheckVisitor<'a, 'b: 'a> {
     resolver: &'a mut Resolver<'b>,
     /// All the (so far) unused imports, grouped path list
     unused_imports: NodeMap<NodeMap<Span>>,
+    /// The spans of the `use` items none of whose imports are used, which
+    /// can therefore be removed altogether.
+    removable_items: NodeMap<Span>,
 }
 
 // Deref and DerefMut impls allow treating UnusedImportCheckVisitor as Resolver.
@@ -108,6 +113,18 @@ impl<'a, 'b> Visitor<'a> for UnusedImportCheckVisitor<'a, 'b> {
                         self.check_import(item.id, item.id, p.span);
                     }
                 }
+
+                // Attributes are not part of the item's span, so removing an
+                // item with attributes would leave them dangling.
+                let imports = match p.node {
+                    ViewPathList(_, ref list) => cmp::max(list.len(), 1),
+                    _ => 1,
+                };
+                let all_unused = self.unused_imports.get(&item.id)
+                                     .map_or(false, |unused| unused.len() == imports);
+                if all_unused && item.attrs.is_empty() {
+                    self.removable_items.insert(item.id, item.span);
+                }
             }
             _ => {}
         }
@@ -137,6 +154,7 @@ pub fn check_crate(resolver: &mut Resolver, krate: &ast::Crate) {
     let mut visitor = UnusedImportCheckVisitor {
         resolver: resolver,
         unused_imports: NodeMap(),
+        removable_items: NodeMap(),
     };
     visit::walk_crate(&mut visitor, krate);
 
@@ -160,9 +178,13 @@ pub fn check_crate(resolver: &mut Resolver, krate: &ast::Crate) {
                           } else {
                               String::new()
                           });
-        visitor.session.add_lint(lint::builtin::UNUSED_IMPORTS,
-                                 *id,
-                                 ms,
-                                 msg);
+        let mut diagnostic = Diagnostic::new(Level::Warning, &msg);
+        diagnostic.set_span(ms);
+        if let Some(&item_span) = visitor.removable_items.get(id) {
+            diagnostic.span_suggestion_machine_applicable(item_span,
+                                                          "remove the unused import",
+                                                          String::new());
+        }
+        visitor.session.add_lint_diagnostic(lint::builtin::UNUSED_IMPORTS, *id, diagnostic);
     }
 }
@@ -121,6 +121,9 @@ pub struct ExternalCrateData {
     pub name: String,
     pub num: CrateNum,
     pub file_name: String,
+    pub disambiguator: String,
+    /// The hash of the build of the crate this crate was compiled against.
+    pub hash: String,
 }
 
 /// Data for enum declarations.
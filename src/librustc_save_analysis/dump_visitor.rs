@@ -135,10 +135,13 @@ impl<'l, 'tcx: 'l, 'll, D: Dump + 'll> DumpVisitor<'l, 'tcx, 'll, D> {
         // Info about all the external crates referenced from this crate.
         let external_crates = self.save_ctxt.get_external_crates().into_iter().map(|c| {
             let lo_loc = self.span.sess.codemap().lookup_char_pos(c.span.lo);
+            let num = CrateNum::from_u32(c.number);
             ExternalCrateData {
                 name: c.name,
-                num: CrateNum::from_u32(c.number),
+                num: num,
                 file_name: SpanUtils::make_path_string(&lo_loc.file.name),
+                disambiguator: self.tcx.sess.cstore.crate_disambiguator(num).to_string(),
+                hash: self.tcx.sess.cstore.crate_hash(num).to_string(),
             }
         }).collect();
 
@@ -27,6 +27,17 @@ pub fn make_def_id(id: NodeId, map: &Map) -> DefId {
     map.opt_local_def_id(id).unwrap_or(null_def_id())
 }
 
+/// The def path of an item of another crate, by which that crate can find the
+/// item in a later build of its own.
+pub fn external_def_path(id: Option<DefId>, tcx: TyCtxt) -> Option<String> {
+    match id {
+        Some(id) if !id.is_local() && id != null_def_id() => {
+            Some(tcx.def_path(id).to_string(tcx))
+        }
+        _ => None,
+    }
+}
+
 pub fn null_def_id() -> DefId {
     DefId {
         krate: CrateNum::from_u32(u32::max_value()),
@@ -151,6 +162,7 @@ pub struct FunctionCallData {
     pub span: SpanData,
     pub scope: DefId,
     pub ref_id: DefId,
+    pub ref_path: Option<String>,
 }
 
 impl Lower for data::FunctionCallData {
@@ -161,6 +173,7 @@ impl Lower for data::FunctionCallData {
             span: SpanData::from_span(self.span, tcx.sess.codemap()),
             scope: make_def_id(self.scope, &tcx.hir),
             ref_id: self.ref_id,
+            ref_path: external_def_path(Some(self.ref_id), tcx),
         }
     }
 }
@@ -207,6 +220,7 @@ pub struct FunctionRefData {
     pub span: SpanData,
     pub scope: DefId,
     pub ref_id: DefId,
+    pub ref_path: Option<String>,
 }
 
 impl Lower for data::FunctionRefData {
@@ -217,6 +231,7 @@ impl Lower for data::FunctionRefData {
             span: SpanData::from_span(self.span, tcx.sess.codemap()),
             scope: make_def_id(self.scope, &tcx.hir),
             ref_id: self.ref_id,
+            ref_path: external_def_path(Some(self.ref_id), tcx),
         }
     }
 }
@@ -317,6 +332,7 @@ pub struct MethodCallData {
     pub scope: DefId,
     pub ref_id: Option<DefId>,
     pub decl_id: Option<DefId>,
+    pub ref_path: Option<String>,
 }
 
 impl Lower for data::MethodCallData {
@@ -328,6 +344,7 @@ impl Lower for data::MethodCallData {
             scope: make_def_id(self.scope, &tcx.hir),
             ref_id: self.ref_id,
             decl_id: self.decl_id,
+            ref_path: external_def_path(self.ref_id.or(self.decl_id), tcx),
         }
     }
 }
@@ -408,6 +425,7 @@ pub struct ModRefData {
     pub span: SpanData,
     pub scope: DefId,
     pub ref_id: Option<DefId>,
+    pub ref_path: Option<String>,
     pub qualname: String
 }
 
@@ -419,6 +437,7 @@ impl Lower for data::ModRefData {
             span: SpanData::from_span(self.span, tcx.sess.codemap()),
             scope: make_def_id(self.scope, &tcx.hir),
             ref_id: self.ref_id,
+            ref_path: external_def_path(self.ref_id, tcx),
             qualname: self.qualname,
         }
     }
@@ -596,6 +615,7 @@ pub struct TypeRefData {
     pub span: SpanData,
     pub scope: DefId,
     pub ref_id: Option<DefId>,
+    pub ref_path: Option<String>,
     pub qualname: String,
 }
 
@@ -607,6 +627,7 @@ impl Lower for data::TypeRefData {
             span: SpanData::from_span(self.span, tcx.sess.codemap()),
             scope: make_def_id(self.scope, &tcx.hir),
             ref_id: self.ref_id,
+            ref_path: external_def_path(self.ref_id, tcx),
             qualname: self.qualname,
         }
     }
@@ -706,6 +727,7 @@ pub struct VariableRefData {
     pub span: SpanData,
     pub scope: DefId,
     pub ref_id: DefId,
+    pub ref_path: Option<String>,
 }
 
 impl Lower for data::VariableRefData {
@@ -717,6 +739,7 @@ impl Lower for data::VariableRefData {
             span: SpanData::from_span(self.span, tcx.sess.codemap()),
             scope: make_def_id(self.scope, &tcx.hir),
             ref_id: self.ref_id,
+            ref_path: external_def_path(Some(self.ref_id), tcx),
         }
     }
 }
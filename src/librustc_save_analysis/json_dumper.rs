@@ -434,6 +434,9 @@ struct Ref {
     kind: RefKind,
     span: SpanData,
     ref_id: Id,
+    /// The def path of the referenced item if it is in another crate, see
+    /// `external_data::external_def_path`.
+    ref_path: Option<String>,
 }
 
 impl From<FunctionRefData> for Ref {
@@ -442,6 +445,7 @@ impl From<FunctionRefData> for Ref {
             kind: RefKind::Function,
             span: data.span,
             ref_id: From::from(data.ref_id),
+            ref_path: data.ref_path,
         }
     }
 }
@@ -451,6 +455,7 @@ impl From<FunctionCallData> for Ref {
             kind: RefKind::Function,
             span: data.span,
             ref_id: From::from(data.ref_id),
+            ref_path: data.ref_path,
         }
     }
 }
@@ -460,6 +465,7 @@ impl From<MethodCallData> for Ref {
             kind: RefKind::Function,
             span: data.span,
             ref_id: From::from(data.ref_id.or(data.decl_id).unwrap_or(null_def_id())),
+            ref_path: data.ref_path,
         }
     }
 }
@@ -469,6 +475,7 @@ impl From<ModRefData> for Ref {
             kind: RefKind::Mod,
             span: data.span,
             ref_id: From::from(data.ref_id.unwrap_or(null_def_id())),
+            ref_path: data.ref_path,
         }
     }
 }
@@ -478,6 +485,7 @@ impl From<TypeRefData> for Ref {
             kind: RefKind::Type,
             span: data.span,
             ref_id: From::from(data.ref_id.unwrap_or(null_def_id())),
+            ref_path: data.ref_path,
         }
     }
 }
@@ -487,6 +495,7 @@ impl From<VariableRefData> for Ref {
             kind: RefKind::Variable,
             span: data.span,
             ref_id: From::from(data.ref_id),
+            ref_path: data.ref_path,
         }
     }
 }
@@ -3932,11 +3932,11 @@ impl<'a, 'gcx, 'tcx> FnCtxt<'a, 'gcx, 'tcx> {
                                             ast::LitIntType::Unsuffixed) = lit.node {
                                       let snip = tcx.sess.codemap().span_to_snippet(base.span);
                                       if let Ok(snip) = snip {
-                                          err.span_suggestion(expr.span,
-                                                              "to access tuple elements, \
-                                                               use tuple indexing syntax \
-                                                               as shown",
-                                                              format!("{}.{}", snip, i));
+                                          err.span_suggestion_machine_applicable(
+                                              expr.span,
+                                              "to access tuple elements, use tuple indexing \
+                                               syntax as shown",
+                                              format!("{}.{}", snip, i));
                                           needs_note = false;
                                       }
                                   }
@@ -0,0 +1,692 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Compares the public API of two versions of a crate, `--api-diff OLD`.
+//!
+//! Both versions are read from the output of `--output-format json` with the
+//! default passes, and decoded back into the clean items they were written
+//! from. Every public item, field, variant, trait item, inherent associated
+//! item and trait implementation is then looked up by its path in the other
+//! version, where inherent associated items are under the path of their type
+//! without its parameters, and each difference is classified following the
+//! API evolution RFC (1105):
+//!
+//! * breaking: items, fields and trait impls which were removed, changed
+//!   signatures, structs gaining private fields, fields added to structs whose
+//!   fields are all public, variants added to enums, trait items added without
+//!   a default and functions which are no longer `const`;
+//! * minor: any other additions, trait items gaining a default, structs
+//!   losing their private fields, functions becoming `const` and
+//!   deprecations;
+//! * patch: anything else, e.g. changes to docs.
+//!
+//! Signatures are compared on their types, generics and where clauses, but
+//! not on the names of arguments or the ids of the definitions they refer
+//! to, which differ between builds. Auto traits like `Send` and `Sync` are
+//! only compared where the crate implements them explicitly, since the
+//! implementations the compiler infers aren't part of the clean items.
+//!
+//! The changes are printed one per line, or with `--output-format json` as
+//! an object with the `format_version` of the report, the `name` of the
+//! crate, the `required_bump` (`major`, `minor` or `patch`) and the list of
+//! `changes`, each with the `path` and `kind` of the item, the `change`, its
+//! `severity` and a `description`.
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::prelude::*;
+use std::io;
+use std::path::Path;
+
+use rustc::hir;
+use serialize::{Decodable, Encodable, Encoder};
+use serialize::json::{self, Json};
+
+use clean;
+use json::FORMAT_VERSION;
+
+/// The version of the layout of the JSON report.
+pub const REPORT_VERSION: u32 = 1;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Severity {
+    Patch,
+    Minor,
+    Breaking,
+}
+
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            Severity::Patch => "patch",
+            Severity::Minor => "minor",
+            Severity::Breaking => "breaking",
+        }
+    }
+
+    /// The part of the version which has to be increased for a change.
+    pub fn bump(&self) -> &'static str {
+        match *self {
+            Severity::Patch => "patch",
+            Severity::Minor => "minor",
+            Severity::Breaking => "major",
+        }
+    }
+}
+
+impl Encodable for Severity {
+    fn encode<S: Encoder>(&self, s: &mut S) -> Result<(), S::Error> {
+        s.emit_str(self.as_str())
+    }
+}
+
+#[derive(RustcEncodable)]
+pub struct Change {
+    pub path: String,
+    pub kind: &'static str,
+    pub change: &'static str,
+    pub severity: Severity,
+    pub description: String,
+}
+
+#[derive(RustcEncodable)]
+struct Report<'a> {
+    format_version: u32,
+    name: &'a str,
+    required_bump: &'static str,
+    changes: &'a [Change],
+}
+
+/// What is compared of an item of the public API.
+struct ApiItem {
+    kind: &'static str,
+    /// The encoded types, generics and bounds of the item.
+    signature: String,
+    /// Why adding the item breaks code, if it does.
+    breaking_addition: Option<&'static str>,
+    private_fields: bool,
+    /// Whether implementations of the trait the item is in must define it.
+    required: bool,
+    constness: bool,
+    deprecated: bool,
+}
+
+/// The public API of a crate by path.
+type Api = BTreeMap<String, ApiItem>;
+
+pub fn run(old: &Path, new: &Path, output_format: Option<&str>) -> isize {
+    let (old_name, old_module) = match load(old) {
+        Ok(krate) => krate,
+        Err(e) => {
+            let _ = writeln!(&mut io::stderr(), "rustdoc: {}", e);
+            return 1;
+        }
+    };
+    let (name, new_module) = match load(new) {
+        Ok(krate) => krate,
+        Err(e) => {
+            let _ = writeln!(&mut io::stderr(), "rustdoc: {}", e);
+            return 1;
+        }
+    };
+    if old_name != name {
+        let _ = writeln!(&mut io::stderr(),
+                         "rustdoc: cannot compare the APIs of different crates, `{}` and `{}`",
+                         old_name, name);
+        return 1;
+    }
+
+    let mut old_api = Api::new();
+    let mut new_api = Api::new();
+    if let Some(ref module) = old_module {
+        collect(module, "", None, &mut old_api);
+    }
+    if let Some(ref module) = new_module {
+        collect(module, "", None, &mut new_api);
+    }
+    let changes = diff(&old_api, &new_api);
+    let bump = changes.iter().map(|c| c.severity).max().unwrap_or(Severity::Patch).bump();
+
+    match output_format {
+        Some("json") => {
+            let report = Report {
+                format_version: REPORT_VERSION,
+                name: &name,
+                required_bump: bump,
+                changes: &changes,
+            };
+            println!("{}", json::as_json(&report));
+        }
+        Some("text") | None => {
+            for change in &changes {
+                println!("{}: {} `{}` {}",
+                         change.severity.as_str(), change.kind, change.path, change.description);
+            }
+            println!("{} API changes to `{}`, a {} version bump is required",
+                     changes.len(), name, bump);
+        }
+        Some(s) => {
+            let _ = writeln!(&mut io::stderr(),
+                             "rustdoc: unknown output format for --api-diff: {}", s);
+            return 1;
+        }
+    }
+    0
+}
+
+/// Reads the name and the root module of a crate from the output of
+/// `--output-format json`.
+fn load(path: &Path) -> Result<(String, Option<clean::Item>), String> {
+    let mut contents = String::new();
+    File::open(path).and_then(|mut f| f.read_to_string(&mut contents)).map_err(|e| {
+        format!("failed to read `{}`: {}", path.display(), e)
+    })?;
+    let krate = Json::from_str(&contents).map_err(|e| {
+        format!("`{}` is not valid JSON: {}", path.display(), e)
+    })?;
+    if krate.find("format_version").and_then(|v| v.as_u64()) != Some(FORMAT_VERSION as u64) {
+        return Err(format!("`{}` was not written by `--output-format json` with format \
+                            version {}", path.display(), FORMAT_VERSION));
+    }
+    let name = match krate.find("name").and_then(|n| n.as_string()) {
+        Some(name) => name.to_string(),
+        None => return Err(format!("`{}` has no crate name", path.display())),
+    };
+    let module = krate.find("module").cloned().unwrap_or(Json::Null);
+    let module = Decodable::decode(&mut json::Decoder::new(module)).map_err(|e| {
+        format!("failed to decode the items in `{}`: {}", path.display(), e)
+    })?;
+    Ok((name, module))
+}
+
+/// Adds `item` and the items it contains to `api`. `breaking_addition` is
+/// set for the fields and variants of a type if adding them breaks code.
+fn collect(item: &clean::Item, prefix: &str, breaking_addition: Option<&'static str>,
+           api: &mut Api) {
+    let mut path = match item.name {
+        Some(ref name) if prefix.is_empty() => name.clone(),
+        Some(ref name) => format!("{}::{}", prefix, name),
+        None => prefix.to_string(),
+    };
+    let mut entry = ApiItem {
+        kind: item.type_().css_class(),
+        signature: String::new(),
+        breaking_addition: breaking_addition,
+        private_fields: false,
+        required: false,
+        constness: false,
+        deprecated: item.deprecation.is_some() ||
+                    item.stability.as_ref().map_or(false, |s| !s.deprecated_since.is_empty()),
+    };
+    // The items below this one and why adding to them breaks code, if it does.
+    let mut children: (&[clean::Item], Option<&'static str>) = (&[], None);
+
+    match item.inner {
+        clean::StrippedItem(..) |
+        clean::ExternCrateItem(..) |
+        clean::PrimitiveItem(..) |
+        clean::DefaultImplItem(..) => return,
+        // Paths are relative to the crate root.
+        clean::ModuleItem(clean::Module { ref items, is_crate: true }) => {
+            for item in items {
+                collect(item, "", None, api);
+            }
+            return;
+        }
+        clean::ModuleItem(ref m) => children = (&m.items[..], None),
+        clean::ImplItem(ref i) => {
+            match i.trait_ {
+                // The methods and associated items of inherent impls belong to
+                // the type, while those of trait impls are given by the trait.
+                None => {
+                    let for_ = impl_type_path(&i.for_);
+                    let prefix = if prefix.is_empty() {
+                        for_
+                    } else {
+                        format!("{}::{}", prefix, for_)
+                    };
+                    for item in &i.items {
+                        collect(item, &prefix, None, api);
+                    }
+                    return;
+                }
+                Some(ref trait_) => {
+                    let negative = match i.polarity {
+                        Some(clean::ImplPolarity::Negative) => "!",
+                        _ => "",
+                    };
+                    path = format!("impl {}{} for {}", negative, type_name(trait_),
+                                   type_name(&i.for_));
+                    entry.signature = signature(&(i.unsafety, &i.generics));
+                }
+            }
+        }
+        clean::ImportItem(ref import) => {
+            let (name, source) = match *import {
+                clean::Import::Simple(ref name, ref source) => (&name[..], source),
+                clean::Import::Glob(ref source) => ("*", source),
+            };
+            path = if prefix.is_empty() {
+                name.to_string()
+            } else {
+                format!("{}::{}", prefix, name)
+            };
+            entry.signature = path_name(&source.path);
+        }
+        clean::StructItem(ref s) => {
+            entry.signature = signature(&(&s.struct_type, &s.generics));
+            entry.private_fields = s.fields_stripped;
+            children = (&s.fields[..], fields_addition(s.fields_stripped));
+        }
+        clean::UnionItem(ref u) => {
+            entry.signature = signature(&(&u.struct_type, &u.generics));
+            entry.private_fields = u.fields_stripped;
+            children = (&u.fields[..], fields_addition(u.fields_stripped));
+        }
+        clean::EnumItem(ref e) => {
+            entry.signature = signature(&e.generics);
+            children = (&e.variants[..], Some("to an enum, which breaks exhaustive matches"));
+        }
+        clean::VariantItem(ref v) => {
+            match v.kind {
+                clean::VariantKind::CLike => {}
+                clean::VariantKind::Tuple(ref types) => entry.signature = signature(types),
+                clean::VariantKind::Struct(ref s) => {
+                    entry.signature = signature(&s.struct_type);
+                    entry.private_fields = s.fields_stripped;
+                    children = (&s.fields[..], fields_addition(s.fields_stripped));
+                }
+            }
+        }
+        clean::FunctionItem(ref f) | clean::ForeignFunctionItem(ref f) => {
+            entry.signature = signature(&(decl_signature(&f.decl), &f.generics, f.unsafety,
+                                          f.abi));
+            entry.constness = f.constness == hir::Constness::Const;
+        }
+        clean::MethodItem(ref m) => {
+            entry.signature = signature(&(decl_signature(&m.decl), &m.generics, m.unsafety,
+                                          m.abi));
+            entry.constness = m.constness == hir::Constness::Const;
+        }
+        clean::TyMethodItem(ref m) => {
+            entry.signature = signature(&(decl_signature(&m.decl), &m.generics, m.unsafety,
+                                          m.abi));
+            entry.required = true;
+        }
+        clean::TypedefItem(ref t, _) => entry.signature = signature(&(&t.type_, &t.generics)),
+        clean::StaticItem(ref s) | clean::ForeignStaticItem(ref s) => {
+            entry.signature = signature(&(&s.type_, s.mutability));
+        }
+        clean::ConstantItem(ref c) => entry.signature = signature(&c.type_),
+        clean::TraitItem(ref t) => {
+            entry.signature = signature(&(t.unsafety, &t.generics, &t.bounds));
+            children = (&t.items[..], None);
+        }
+        clean::AssociatedConstItem(ref type_, ref default) => {
+            entry.signature = signature(type_);
+            entry.required = default.is_none();
+        }
+        clean::AssociatedTypeItem(ref bounds, ref default) => {
+            entry.signature = signature(bounds);
+            entry.required = default.is_none();
+        }
+        clean::StructFieldItem(ref type_) => entry.signature = signature(type_),
+        clean::MacroItem(..) => path.push('!'),
+    }
+
+    if entry.required {
+        entry.breaking_addition = Some("to a trait without a default, which breaks its \
+                                        implementations");
+    }
+    api.insert(path.clone(), entry);
+    for item in children.0 {
+        collect(item, &path, children.1, api);
+    }
+}
+
+fn fields_addition(fields_stripped: bool) -> Option<&'static str> {
+    if fields_stripped {
+        None
+    } else {
+        Some("to a type whose fields are all public, which breaks its literals and patterns")
+    }
+}
+
+/// Encodes the parts of an item which are compared, leaving out what
+/// differs between builds of the same API: the ids of definitions, spans,
+/// and attributes, which include the docs.
+fn signature<T: Encodable>(parts: &T) -> String {
+    let encoded = json::encode(parts).expect("failed to encode a signature");
+    let mut signature = Json::from_str(&encoded).expect("failed to parse an encoded signature");
+    strip_build_details(&mut signature);
+    signature.to_string()
+}
+
+fn strip_build_details(json: &mut Json) {
+    match *json {
+        Json::Object(ref mut object) => {
+            for key in &["did", "def", "def_id", "span", "source", "attrs"] {
+                object.remove(*key);
+            }
+            for value in object.values_mut() {
+                strip_build_details(value);
+            }
+        }
+        Json::Array(ref mut values) => {
+            for value in values {
+                strip_build_details(value);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// The declaration of a function without the names of its arguments, which
+/// callers don't see.
+fn decl_signature(decl: &clean::FnDecl) -> clean::FnDecl {
+    let mut decl = decl.clone();
+    for argument in &mut decl.inputs.values {
+        if argument.name != "self" {
+            argument.name = String::new();
+        }
+    }
+    decl
+}
+
+fn diff(old: &Api, new: &Api) -> Vec<Change> {
+    let mut changes = vec![];
+    {
+        let mut change = |path: &str, item: &ApiItem, what: &'static str, severity: Severity,
+                          description: &str| {
+            changes.push(Change {
+                path: path.to_string(),
+                kind: item.kind,
+                change: what,
+                severity: severity,
+                description: description.to_string(),
+            });
+        };
+
+        for (path, old_item) in old {
+            let item = match new.get(path) {
+                Some(item) => item,
+                None => {
+                    change(path, old_item, "removed", Severity::Breaking, "was removed");
+                    continue;
+                }
+            };
+            if item.kind != old_item.kind {
+                // Re-exports may be documented inline as the item itself.
+                if item.kind != "import" && old_item.kind != "import" {
+                    let description = format!("changed from {} to {}", old_item.kind, item.kind);
+                    change(path, item, "kind", Severity::Breaking, &description);
+                }
+                continue;
+            }
+            if item.signature != old_item.signature {
+                change(path, item, "signature", Severity::Breaking, "changed its signature");
+            }
+            match (old_item.private_fields, item.private_fields) {
+                (false, true) => {
+                    change(path, item, "private_fields_added", Severity::Breaking,
+                           "gained private fields");
+                }
+                (true, false) => {
+                    change(path, item, "private_fields_removed", Severity::Minor,
+                           "no longer has private fields");
+                }
+                _ => {}
+            }
+            match (old_item.required, item.required) {
+                (false, true) => {
+                    change(path, item, "default_removed", Severity::Breaking,
+                           "no longer has a default");
+                }
+                (true, false) => {
+                    change(path, item, "default_added", Severity::Minor, "gained a default");
+                }
+                _ => {}
+            }
+            match (old_item.constness, item.constness) {
+                (true, false) => {
+                    change(path, item, "const_removed", Severity::Breaking,
+                           "is no longer `const`");
+                }
+                (false, true) => {
+                    change(path, item, "const_added", Severity::Minor, "is now `const`");
+                }
+                _ => {}
+            }
+            match (old_item.deprecated, item.deprecated) {
+                (false, true) => {
+                    change(path, item, "deprecated", Severity::Minor, "was deprecated");
+                }
+                (true, false) => {
+                    change(path, item, "undeprecated", Severity::Patch,
+                           "is no longer deprecated");
+                }
+                _ => {}
+            }
+        }
+
+        for (path, item) in new {
+            if old.contains_key(path) {
+                continue;
+            }
+            match item.breaking_addition {
+                Some(reason) => {
+                    let description = format!("was added {}", reason);
+                    change(path, item, "added", Severity::Breaking, &description);
+                }
+                None => change(path, item, "added", Severity::Minor, "was added"),
+            }
+        }
+    }
+    changes.sort_by(|a, b| (b.severity, &a.path).cmp(&(a.severity, &b.path)));
+    changes
+}
+
+/// The path of the type of an inherent impl without its parameters, e.g.
+/// `Point` for `impl<T> Point<T>`, so that renaming the parameters of the
+/// impl doesn't move its items.
+fn impl_type_path(type_: &clean::Type) -> String {
+    match *type_ {
+        clean::ResolvedPath { ref path, .. } => {
+            path.segments.iter().map(|segment| &segment.name[..]).collect::<Vec<_>>().join("::")
+        }
+        _ => type_name(type_),
+    }
+}
+
+/// Writes a type the way it is written in Rust, for the paths of the items
+/// of impls.
+fn type_name(type_: &clean::Type) -> String {
+    match *type_ {
+        clean::ResolvedPath { ref path, .. } => path_name(path),
+        clean::Generic(ref name) => name.clone(),
+        clean::Primitive(primitive) => primitive.as_str().to_string(),
+        clean::BareFunction(ref f) => {
+            let inputs: Vec<_> = f.decl.inputs.values.iter().map(|argument| {
+                type_name(&argument.type_)
+            }).collect();
+            match f.decl.output {
+                clean::Return(ref output) => {
+                    format!("fn({}) -> {}", inputs.join(", "), type_name(output))
+                }
+                clean::DefaultReturn => format!("fn({})", inputs.join(", ")),
+            }
+        }
+        clean::Tuple(ref types) => format!("({})", type_names(types)),
+        clean::Vector(ref type_) => format!("[{}]", type_name(type_)),
+        clean::FixedVector(ref type_, ref len) => format!("[{}; {}]", type_name(type_), len),
+        clean::Never => "!".to_string(),
+        clean::Unique(ref type_) => format!("Box<{}>", type_name(type_)),
+        clean::RawPointer(clean::Mutable, ref type_) => format!("*mut {}", type_name(type_)),
+        clean::RawPointer(clean::Immutable, ref type_) => format!("*const {}", type_name(type_)),
+        clean::BorrowedRef { ref lifetime, mutability, ref type_ } => {
+            let lifetime = match *lifetime {
+                Some(ref lifetime) => format!("{} ", lifetime.get_ref()),
+                None => String::new(),
+            };
+            let mutability = match mutability {
+                clean::Mutable => "mut ",
+                clean::Immutable => "",
+            };
+            format!("&{}{}{}", lifetime, mutability, type_name(type_))
+        }
+        clean::QPath { ref name, ref self_type, ref trait_ } => {
+            format!("<{} as {}>::{}", type_name(self_type), type_name(trait_), name)
+        }
+        clean::Infer => "_".to_string(),
+        clean::ImplTrait(..) => "impl Trait".to_string(),
+    }
+}
+
+fn type_names(types: &[clean::Type]) -> String {
+    types.iter().map(type_name).collect::<Vec<_>>().join(", ")
+}
+
+fn path_name(path: &clean::Path) -> String {
+    path.segments.iter().map(|segment| {
+        match segment.params {
+            clean::PathParameters::AngleBracketed { ref types, .. } if !types.is_empty() => {
+                format!("{}<{}>", segment.name, type_names(types))
+            }
+            _ => segment.name.clone(),
+        }
+    }).collect::<Vec<_>>().join("::")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Api, ApiItem, Change, Severity, diff, impl_type_path};
+    use clean;
+    use rustc::hir::def::Def;
+    use rustc::hir::def_id::{CRATE_DEF_INDEX, DefId};
+
+    fn item(kind: &'static str, signature: &str) -> ApiItem {
+        ApiItem {
+            kind: kind,
+            signature: signature.to_string(),
+            breaking_addition: None,
+            private_fields: false,
+            required: false,
+            constness: false,
+            deprecated: false,
+        }
+    }
+
+    fn changes(changes: &[Change]) -> Vec<(&str, &str, Severity)> {
+        changes.iter().map(|c| (&c.path[..], c.change, c.severity)).collect()
+    }
+
+    #[test]
+    fn no_changes() {
+        let mut api = Api::new();
+        api.insert("foo".to_string(), item("fn", "a"));
+        api.insert("Bar".to_string(), item("struct", "b"));
+        let same: Api = api.iter().map(|(path, i)| (path.clone(), item(i.kind, &i.signature)))
+                                  .collect();
+        assert!(diff(&api, &same).is_empty());
+    }
+
+    #[test]
+    fn removed_and_added() {
+        let mut old = Api::new();
+        old.insert("foo".to_string(), item("fn", "a"));
+        let mut new = Api::new();
+        new.insert("bar".to_string(), item("fn", "a"));
+        let mut variant = item("variant", "");
+        variant.breaking_addition = Some("to an enum, which breaks exhaustive matches");
+        new.insert("E::B".to_string(), variant);
+        assert_eq!(changes(&diff(&old, &new)),
+                   vec![("E::B", "added", Severity::Breaking),
+                        ("foo", "removed", Severity::Breaking),
+                        ("bar", "added", Severity::Minor)]);
+    }
+
+    #[test]
+    fn changed_items() {
+        let mut old = Api::new();
+        let mut new = Api::new();
+        old.insert("sig".to_string(), item("fn", "a"));
+        new.insert("sig".to_string(), item("fn", "b"));
+        old.insert("kind".to_string(), item("fn", "a"));
+        new.insert("kind".to_string(), item("macro", "a"));
+        // A re-export documented inline is the same item.
+        old.insert("reexport".to_string(), item("import", "a"));
+        new.insert("reexport".to_string(), item("struct", "b"));
+
+        let mut fields = item("struct", "a");
+        fields.private_fields = true;
+        old.insert("fields".to_string(), item("struct", "a"));
+        new.insert("fields".to_string(), fields);
+
+        let mut default = item("tymethod", "a");
+        default.required = true;
+        old.insert("default".to_string(), default);
+        new.insert("default".to_string(), item("tymethod", "a"));
+
+        let mut constness = item("fn", "a");
+        constness.constness = true;
+        old.insert("constness".to_string(), constness);
+        new.insert("constness".to_string(), item("fn", "a"));
+
+        let mut deprecated = item("fn", "a");
+        deprecated.deprecated = true;
+        old.insert("deprecated".to_string(), item("fn", "a"));
+        new.insert("deprecated".to_string(), deprecated);
+
+        assert_eq!(changes(&diff(&old, &new)),
+                   vec![("constness", "const_removed", Severity::Breaking),
+                        ("fields", "private_fields_added", Severity::Breaking),
+                        ("kind", "kind", Severity::Breaking),
+                        ("sig", "signature", Severity::Breaking),
+                        ("default", "default_added", Severity::Minor),
+                        ("deprecated", "deprecated", Severity::Minor)]);
+        assert_eq!(changes(&diff(&new, &old)),
+                   vec![("default", "default_removed", Severity::Breaking),
+                        ("kind", "kind", Severity::Breaking),
+                        ("sig", "signature", Severity::Breaking),
+                        ("constness", "const_added", Severity::Minor),
+                        ("fields", "private_fields_removed", Severity::Minor),
+                        ("deprecated", "undeprecated", Severity::Patch)]);
+    }
+
+    #[test]
+    fn inherent_impl_paths() {
+        fn segment(name: &str, params: Vec<clean::Type>) -> clean::PathSegment {
+            clean::PathSegment {
+                name: name.to_string(),
+                params: clean::PathParameters::AngleBracketed {
+                    lifetimes: vec![],
+                    types: params,
+                    bindings: vec![],
+                },
+            }
+        }
+        let point = |param: &str| {
+            clean::ResolvedPath {
+                path: clean::Path {
+                    global: false,
+                    def: Def::Err,
+                    segments: vec![segment("geometry", vec![]),
+                                   segment("Point", vec![clean::Generic(param.to_string())])],
+                },
+                typarams: None,
+                did: DefId::local(CRATE_DEF_INDEX),
+                is_generic: false,
+            }
+        };
+        assert_eq!(impl_type_path(&point("T")), "geometry::Point");
+        assert_eq!(impl_type_path(&point("T")), impl_type_path(&point("U")));
+        assert_eq!(impl_type_path(&clean::Primitive(clean::PrimitiveType::Str)), "str");
+    }
+}
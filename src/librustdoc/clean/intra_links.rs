@@ -0,0 +1,438 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Resolves the Rust paths used as links in docs, like ``[`Vec::push`]``,
+//! in the scope of the item they document.
+//!
+//! A path is looked up in the module of the item, then in the crate root and
+//! the prelude the crate imports. Each of its segments but the last names a
+//! module or the root of an extern crate, and the last may also name a field,
+//! variant or associated item of the type, type alias or trait before it.
+//! `Self` names the type or trait the item is or belongs to. The items inside
+//! blocks aren't documented, so their docs are never resolved.
+//!
+//! Like in Rust, modules and types have names apart from values and macros:
+//! `type@foo` names a module or type, `value@foo` and `foo()` a function,
+//! constant or static, and `macro@foo` and `foo!` a macro. A path without
+//! any of these which names items of several namespaces is ambiguous.
+//!
+//! The resolver has finished by the time rustdoc runs and doesn't keep its
+//! scopes, so the lookup here follows its rules only for the common cases.
+//! It doesn't cover:
+//!
+//! - generic parameters, which name no documented item;
+//! - macros other than those exported from the crate root, those of extern
+//!   crates and those of the prelude; the scopes of `macro_rules!` and
+//!   `#[macro_use]` are ignored;
+//! - glob imports of anything but modules and enums;
+//! - imports naming items of several namespaces, which are only found in
+//!   the namespace the lowered `use` item records.
+//!
+//! The resolutions are kept in `Attributes::links` and turned into URLs when
+//! the docs are rendered; the paths which can't be resolved are warned about.
+
+use rustc::hir;
+use rustc::hir::def::Def;
+use rustc::hir::def_id::{DefId, CRATE_DEF_INDEX};
+use rustc::ty::{self, DefIdTree, Ty};
+use syntax::ast;
+use syntax::attr;
+
+use core::DocContext;
+use fold::DocFolder;
+use html::markdown;
+
+use super::Item;
+
+/// Resolves the links in the docs of the local items below `module`.
+pub fn resolve_links(cx: &DocContext, module: Item) -> Item {
+    LinkCollector { cx: cx }.fold_item(module).unwrap()
+}
+
+struct LinkCollector<'a, 'b: 'a, 'tcx: 'b> {
+    cx: &'a DocContext<'b, 'tcx>,
+}
+
+impl<'a, 'b, 'tcx> DocFolder for LinkCollector<'a, 'b, 'tcx> {
+    fn fold_item(&mut self, mut item: Item) -> Option<Item> {
+        let node_id = self.cx.tcx.hir.as_local_node_id(item.def_id);
+        if let Some(node_id) = node_id {
+            let paths = markdown::rust_links(&item.attrs.doc_strings.join("\n"));
+            let scope = Scope {
+                // The docs of a module are written in its scope.
+                module: if item.is_mod() {
+                    item.def_id
+                } else {
+                    self.cx.tcx.hir.local_def_id(self.cx.tcx.hir.get_module_parent(node_id))
+                },
+                self_def: self_def(self.cx, &item, node_id),
+            };
+            for path in paths {
+                if item.attrs.links.iter().any(|&(ref p, ..)| *p == path) {
+                    continue;
+                }
+                match resolve(self.cx, &scope, &path) {
+                    Ok((did, fragment)) => item.attrs.links.push((path, did, fragment)),
+                    Err(msg) => {
+                        match item.attrs.span {
+                            Some(span) => self.cx.sess().span_warn(span, &msg),
+                            None => self.cx.sess().warn(&msg),
+                        }
+                    }
+                }
+            }
+        }
+        self.fold_item_recur(item)
+    }
+}
+
+/// The namespaces of the names paths resolve to, so that e.g. a module and a
+/// function of the same name are told apart.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Namespace {
+    Type,
+    Value,
+    Macro,
+}
+
+impl Namespace {
+    fn of(def: Def) -> Option<Namespace> {
+        match def {
+            Def::Mod(..) | Def::Struct(..) | Def::Union(..) | Def::Enum(..) |
+            Def::Variant(..) | Def::Trait(..) | Def::TyAlias(..) => Some(Namespace::Type),
+            Def::Fn(..) | Def::Const(..) | Def::Static(..) => Some(Namespace::Value),
+            Def::Macro(..) => Some(Namespace::Macro),
+            _ => None,
+        }
+    }
+}
+
+/// Splits the namespace a link asks for from its path, e.g. `value@foo`,
+/// `foo()` and `foo!`.
+fn split_namespace(link: &str) -> (Option<Namespace>, &str) {
+    for &(prefix, ns) in &[("type@", Namespace::Type),
+                           ("value@", Namespace::Value),
+                           ("macro@", Namespace::Macro)] {
+        if link.starts_with(prefix) {
+            return (Some(ns), &link[prefix.len()..]);
+        }
+    }
+    if link.ends_with("()") {
+        (Some(Namespace::Value), &link[..link.len() - 2])
+    } else if link.ends_with('!') {
+        (Some(Namespace::Macro), &link[..link.len() - 1])
+    } else {
+        (None, link)
+    }
+}
+
+/// Where the paths in the docs of an item are resolved.
+struct Scope {
+    /// The module of the item, or the item itself if it is a module.
+    module: DefId,
+    /// What `Self` names, if anything.
+    self_def: Option<Def>,
+}
+
+/// The type or trait `item` is or belongs to, which `Self` names in its
+/// docs.
+fn self_def(cx: &DocContext, item: &Item, node_id: ast::NodeId) -> Option<Def> {
+    let tcx = cx.tcx;
+    match item.inner {
+        super::StructItem(..) | super::UnionItem(..) | super::EnumItem(..) => {
+            adt_def(tcx.item_type(item.def_id))
+        }
+        super::TraitItem(..) => Some(Def::Trait(item.def_id)),
+        super::ImplItem(..) => adt_def(tcx.item_type(item.def_id)),
+        super::StructFieldItem(..) | super::VariantItem(..) => {
+            adt_def(tcx.item_type(tcx.hir.local_def_id(tcx.hir.get_parent(node_id))))
+        }
+        super::MethodItem(..) | super::TyMethodItem(..) |
+        super::AssociatedConstItem(..) | super::AssociatedTypeItem(..) => {
+            match tcx.associated_item(item.def_id).container {
+                ty::ImplContainer(impl_did) => adt_def(tcx.item_type(impl_did)),
+                ty::TraitContainer(trait_did) => Some(Def::Trait(trait_did)),
+            }
+        }
+        _ => None,
+    }
+}
+
+/// The definition of `ty` if it is a struct, union or enum.
+fn adt_def(ty: Ty) -> Option<Def> {
+    ty.ty_adt_def().map(|adt| {
+        match adt.adt_kind() {
+            ty::AdtKind::Struct => Def::Struct(adt.did),
+            ty::AdtKind::Union => Def::Union(adt.did),
+            ty::AdtKind::Enum => Def::Enum(adt.did),
+        }
+    })
+}
+
+/// Resolves `link` in `scope` to the item it names and, for fields, variants
+/// and associated items, the anchor of the item on the page of its parent.
+/// The error is the warning to give about the link.
+fn resolve(cx: &DocContext, scope: &Scope, link: &str)
+           -> Result<(DefId, Option<String>), String> {
+    let unresolved = || format!("unresolved link to `{}`", link);
+    let (ns, path) = split_namespace(link);
+    let (mut module, mut relative, path) = if path.starts_with("::") {
+        (DefId::local(CRATE_DEF_INDEX), true, &path[2..])
+    } else {
+        (scope.module, false, path)
+    };
+    let mut segments = path.split("::").peekable();
+    loop {
+        match segments.peek() {
+            Some(&"self") => {}
+            Some(&"super") => {
+                module = match cx.tcx.parent(module) {
+                    Some(parent) => parent,
+                    None => return Err(unresolved()),
+                };
+            }
+            _ => break,
+        }
+        relative = true;
+        segments.next();
+    }
+    let segments: Vec<&str> = segments.collect();
+    let (last, parents) = match segments.split_last() {
+        Some((&last, parents)) => (last, parents),
+        None => return Ok((module, None)),
+    };
+
+    // The segments before the last name modules, types or traits.
+    let mut parent = None;
+    for &segment in parents {
+        let def = match parent {
+            None if segment == "Self" => scope.self_def,
+            None => lookup_start(cx, module, relative, segment, Namespace::Type),
+            Some(Def::Mod(did)) => lookup(cx, did, segment, Namespace::Type, &mut vec![]),
+            // Only the last segment may name a member of a type.
+            Some(_) => None,
+        };
+        parent = match def {
+            Some(def) => Some(def),
+            None => return Err(unresolved()),
+        };
+    }
+    match parent {
+        Some(Def::Mod(did)) => {
+            module = did;
+            relative = true;
+        }
+        Some(def) => return resolve_member(cx, def, last).ok_or_else(unresolved),
+        None if last == "Self" => {
+            return scope.self_def.and_then(|def| resolution(cx, def)).ok_or_else(unresolved);
+        }
+        None => {}
+    }
+
+    let namespaces = match ns {
+        Some(ns) => vec![ns],
+        None => vec![Namespace::Type, Namespace::Value, Namespace::Macro],
+    };
+    let defs: Vec<Def> = namespaces.into_iter().filter_map(|ns| {
+        lookup_start(cx, module, relative, last, ns)
+    }).collect();
+    match defs.len() {
+        0 => Err(unresolved()),
+        1 => resolution(cx, defs[0]).ok_or_else(unresolved),
+        _ => {
+            Err(format!("ambiguous link to `{}`, which names both the {} and the {}; prefix it \
+                         with `type@`, `value@` or `macro@` to choose one",
+                        link, defs[0].kind_name(), defs[1].kind_name()))
+        }
+    }
+}
+
+/// The item and anchor a link to `def` points to, if it has a page of its
+/// own or is a variant.
+fn resolution(cx: &DocContext, def: Def) -> Option<(DefId, Option<String>)> {
+    match def {
+        Def::Mod(did) | Def::Struct(did) | Def::Union(did) | Def::Enum(did) |
+        Def::Trait(did) | Def::TyAlias(did) | Def::Fn(did) | Def::Const(did) |
+        Def::Static(did, _) | Def::Macro(did) => Some((did, None)),
+        Def::Variant(did) => {
+            cx.tcx.parent(did).map(|parent| {
+                (parent, Some(format!("variant.{}", cx.tcx.item_name(did))))
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Resolves the field, variant or associated item `name` of a type or trait.
+/// The members of a type alias are those of the struct, union or enum it
+/// stands for.
+fn resolve_member(cx: &DocContext, def: Def, name: &str) -> Option<(DefId, Option<String>)> {
+    let did = match def {
+        Def::Struct(did) | Def::Union(did) | Def::Enum(did) | Def::Trait(did) => did,
+        Def::TyAlias(did) => {
+            return adt_def(cx.tcx.item_type(did)).and_then(|def| resolve_member(cx, def, name));
+        }
+        _ => return None,
+    };
+    let kind = match def {
+        Def::Enum(_) => {
+            let adt = cx.tcx.lookup_adt_def(did);
+            adt.variants.iter().find(|v| v.name == name).map(|_| "variant")
+        }
+        Def::Struct(_) | Def::Union(_) => {
+            let adt = cx.tcx.lookup_adt_def(did);
+            adt.struct_variant().fields.iter().find(|f| f.name == name).map(|_| "structfield")
+        }
+        _ => None,
+    };
+    let kind = kind.or_else(|| {
+        let items: Vec<ty::AssociatedItem> = match def {
+            Def::Trait(_) => cx.tcx.associated_items(did).collect(),
+            _ => inherent_items(cx, did),
+        };
+        items.iter().find(|item| item.name == name).map(|item| {
+            match item.kind {
+                ty::AssociatedKind::Method if item.defaultness.has_value() => "method",
+                ty::AssociatedKind::Method => "tymethod",
+                ty::AssociatedKind::Type => "associatedtype",
+                ty::AssociatedKind::Const => "associatedconstant",
+            }
+        })
+    });
+    kind.map(|kind| (did, Some(format!("{}.{}", kind, name))))
+}
+
+/// The associated items of the inherent impls of the type `did`.
+fn inherent_items(cx: &DocContext, did: DefId) -> Vec<ty::AssociatedItem> {
+    cx.tcx.populate_inherent_implementations_for_type_if_necessary(did);
+    let impls: Vec<DefId> = match cx.tcx.inherent_impls.borrow().get(&did) {
+        Some(impls) => impls.iter().cloned().collect(),
+        None => vec![],
+    };
+    impls.into_iter().flat_map(|impl_did| cx.tcx.associated_items(impl_did)).collect()
+}
+
+/// Finds the first segment of a path in the namespace `ns`: in `module`,
+/// then, unless the path is relative, in the crate root and the prelude.
+fn lookup_start(cx: &DocContext, module: DefId, relative: bool, name: &str, ns: Namespace)
+                -> Option<Def> {
+    let root = DefId::local(CRATE_DEF_INDEX);
+    lookup(cx, module, name, ns, &mut vec![]).or_else(|| {
+        if relative {
+            None
+        } else {
+            lookup(cx, root, name, ns, &mut vec![]).or_else(|| lookup_in_prelude(cx, name, ns))
+        }
+    })
+}
+
+/// Finds the item named `name` of the namespace `ns` in `module`, including
+/// the items it imports. `globbed` holds the modules whose glob imports are
+/// being looked in.
+fn lookup(cx: &DocContext, module: DefId, name: &str, ns: Namespace, globbed: &mut Vec<DefId>)
+          -> Option<Def> {
+    let in_namespace = |def: Def| Namespace::of(def) == Some(ns) && resolution(cx, def).is_some();
+    let node_id = match cx.tcx.hir.as_local_node_id(module) {
+        Some(node_id) => node_id,
+        None => {
+            return cx.tcx.sess.cstore.item_children(module).into_iter().filter(|child| {
+                child.name == name && in_namespace(child.def)
+            }).map(|child| child.def).next();
+        }
+    };
+    let hir_mod = if module.index == CRATE_DEF_INDEX {
+        // The macros of the crate are exported from its root.
+        if ns == Namespace::Macro {
+            let krate = cx.tcx.hir.krate();
+            if let Some(def) = krate.exported_macros.iter().find(|def| def.name == name) {
+                return Some(Def::Macro(cx.tcx.hir.local_def_id(def.id)));
+            }
+        }
+        &cx.tcx.hir.krate().module
+    } else {
+        match cx.tcx.hir.expect_item(node_id).node {
+            hir::ItemMod(ref m) => m,
+            _ => return None,
+        }
+    };
+
+    let mut globs = vec![];
+    for item_id in &hir_mod.item_ids {
+        let item = cx.tcx.hir.expect_item(item_id.id);
+        let did = cx.tcx.hir.local_def_id(item.id);
+        let def = match item.node {
+            // The prelude is looked in last, by `lookup_in_prelude`.
+            hir::ItemUse(..) if attr::contains_name(&item.attrs, "prelude_import") => continue,
+            hir::ItemUse(ref path, hir::UseKind::Glob) => {
+                globs.push(path.def);
+                continue;
+            }
+            _ if item.name != name => continue,
+            hir::ItemUse(ref path, hir::UseKind::Single) => path.def,
+            hir::ItemExternCrate(_) => {
+                match cx.tcx.sess.cstore.extern_mod_stmt_cnum(item.id) {
+                    Some(cnum) => Def::Mod(DefId { krate: cnum, index: CRATE_DEF_INDEX }),
+                    None => continue,
+                }
+            }
+            hir::ItemMod(..) => Def::Mod(did),
+            hir::ItemStruct(..) => Def::Struct(did),
+            hir::ItemUnion(..) => Def::Union(did),
+            hir::ItemEnum(..) => Def::Enum(did),
+            hir::ItemTrait(..) => Def::Trait(did),
+            hir::ItemTy(..) => Def::TyAlias(did),
+            hir::ItemFn(..) => Def::Fn(did),
+            hir::ItemConst(..) => Def::Const(did),
+            hir::ItemStatic(_, mutbl, _) => Def::Static(did, mutbl == hir::MutMutable),
+            _ => continue,
+        };
+        if in_namespace(def) {
+            return Some(def);
+        }
+    }
+
+    // The items of a module shadow those it imports with globs.
+    globbed.push(module);
+    for def in globs {
+        match def {
+            Def::Mod(did) if !globbed.contains(&did) => {
+                if let Some(def) = lookup(cx, did, name, ns, globbed) {
+                    return Some(def);
+                }
+            }
+            // A glob import of an enum imports its variants.
+            Def::Enum(did) if ns == Namespace::Type => {
+                let adt = cx.tcx.lookup_adt_def(did);
+                if let Some(variant) = adt.variants.iter().find(|v| v.name == name) {
+                    return Some(Def::Variant(variant.did));
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Finds `name` in the prelude the crate imports, usually `std::prelude::v1`
+/// or `core::prelude::v1`.
+fn lookup_in_prelude(cx: &DocContext, name: &str, ns: Namespace) -> Option<Def> {
+    let krate = cx.tcx.hir.krate();
+    for item_id in &krate.module.item_ids {
+        let item = cx.tcx.hir.expect_item(item_id.id);
+        if let hir::ItemUse(ref path, hir::UseKind::Glob) = item.node {
+            if let Def::Mod(prelude) = path.def {
+                if attr::contains_name(&item.attrs, "prelude_import") {
+                    return lookup(cx, prelude, name, ns, &mut vec![]);
+                }
+            }
+        }
+    }
+    None
+}
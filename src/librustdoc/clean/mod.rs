@@ -52,6 +52,7 @@ use visit_ast;
 use html::item_type::ItemType;
 
 pub mod inline;
+mod intra_links;
 mod simplify;
 
 // extract the stability index for a node from tcx, if possible
@@ -136,7 +137,7 @@ impl<'a, 'tcx> Clean<Crate> for visit_ast::RustdocVisitor<'a, 'tcx> {
 
         // Clean the crate, translating the entire libsyntax AST to one that is
         // understood by rustdoc.
-        let mut module = self.module.clean(cx);
+        let mut module = intra_links::resolve_links(cx, self.module.clean(cx));
 
         let ExternalCrate { name, src, primitives, .. } = LOCAL_CRATE.clean(cx);
         {
@@ -523,6 +524,9 @@ pub struct Attributes {
     pub doc_strings: Vec<String>,
     pub other_attrs: Vec<ast::Attribute>,
     pub span: Option<syntax_pos::Span>,
+    /// The Rust paths used as links in the docs, with the item they resolve
+    /// to and the anchor on its page, if any.
+    pub links: Vec<(String, DefId, Option<String>)>,
 }
 
 impl Attributes {
@@ -548,6 +552,7 @@ impl Attributes {
             doc_strings: doc_strings,
             other_attrs: other_attrs,
             span: sp,
+            links: vec![],
         }
     }
 
@@ -102,6 +102,11 @@ impl DocAccessLevels for AccessLevels<DefId> {
     }
 }
 
+/// The handler of the diagnostics rustdoc reports itself, which are all
+/// printed to the terminal.
+pub fn new_handler(codemap: Option<Rc<errors::CodeMapper>>) -> errors::Handler {
+    errors::Handler::with_tty_emitter(ColorConfig::Auto, true, false, codemap)
+}
 
 pub fn run_core(search_paths: SearchPaths,
                 cfgs: Vec<String>,
@@ -134,16 +139,13 @@ pub fn run_core(search_paths: SearchPaths,
     };
 
     let codemap = Rc::new(codemap::CodeMap::new());
-    let diagnostic_handler = errors::Handler::with_tty_emitter(ColorConfig::Auto,
-                                                               true,
-                                                               false,
-                                                               Some(codemap.clone()));
+    let diagnostic_handler = new_handler(Some(codemap.clone() as Rc<errors::CodeMapper>));
 
     let dep_graph = DepGraph::new(false);
     let _ignore = dep_graph.in_ignore();
     let cstore = Rc::new(CStore::new(&dep_graph));
     let mut sess = session::build_session_(
-        sessopts, &dep_graph, cpath, diagnostic_handler, codemap, cstore.clone()
+        sessopts, &dep_graph, cpath, diagnostic_handler, codemap.clone(), cstore.clone()
     );
     rustc_lint::register_builtins(&mut sess.lint_store.borrow_mut(), Some(&sess));
 
@@ -212,6 +214,8 @@ pub fn run_core(search_paths: SearchPaths,
             v.clean(&ctxt)
         };
 
-        (krate, ctxt.renderinfo.into_inner())
+        let mut renderinfo = ctxt.renderinfo.into_inner();
+        renderinfo.codemap = Some(codemap.clone());
+        (krate, renderinfo)
     }), &sess)
 }
@@ -0,0 +1,245 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Counts the public items of a crate which are documented or have examples,
+//! by module and by kind of item, for `--show-coverage`.
+//!
+//! Fields, variants, trait items and the items of inherent impls are counted
+//! in the module of their type or trait, while trait impls are left out as
+//! they're documented by their traits. The strip passes should have run, so
+//! that only the public API is counted.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::iter;
+
+use serialize::json;
+
+use clean;
+use html::markdown;
+
+/// The version of the layout of the JSON output.
+pub const FORMAT_VERSION: u32 = 1;
+
+#[derive(Clone, Copy, Default, Debug, PartialEq)]
+pub struct ItemCount {
+    pub total: u64,
+    pub documented: u64,
+    pub with_examples: u64,
+}
+
+impl ItemCount {
+    /// The percentage of the items which are documented.
+    pub fn percentage(&self) -> f64 {
+        if self.total == 0 {
+            100.0
+        } else {
+            self.documented as f64 * 100.0 / self.total as f64
+        }
+    }
+
+    fn add(&mut self, docs: &str) {
+        self.total += 1;
+        if !docs.trim().is_empty() {
+            self.documented += 1;
+        }
+        if markdown::has_rust_code_block(docs) {
+            self.with_examples += 1;
+        }
+    }
+}
+
+pub struct Coverage {
+    pub name: String,
+    pub total: ItemCount,
+    pub modules: BTreeMap<String, ItemCount>,
+    pub kinds: BTreeMap<&'static str, ItemCount>,
+}
+
+#[derive(RustcEncodable)]
+struct Report<'a> {
+    format_version: u32,
+    name: &'a str,
+    total: Row<'a>,
+    modules: Vec<Row<'a>>,
+    kinds: Vec<Row<'a>>,
+}
+
+#[derive(RustcEncodable)]
+struct Row<'a> {
+    name: &'a str,
+    total: u64,
+    documented: u64,
+    with_examples: u64,
+    percentage: f64,
+}
+
+impl<'a> Row<'a> {
+    fn new(name: &'a str, count: &ItemCount) -> Row<'a> {
+        Row {
+            name: name,
+            total: count.total,
+            documented: count.documented,
+            with_examples: count.with_examples,
+            percentage: (count.percentage() * 10.0).round() / 10.0,
+        }
+    }
+}
+
+impl Coverage {
+    pub fn of(krate: &clean::Crate) -> Coverage {
+        let mut coverage = Coverage {
+            name: krate.name.clone(),
+            total: ItemCount::default(),
+            modules: BTreeMap::new(),
+            kinds: BTreeMap::new(),
+        };
+        if let Some(ref module) = krate.module {
+            coverage.count(module, &krate.name);
+        }
+        coverage
+    }
+
+    /// The coverage as a JSON object with the `name` of the crate, the
+    /// `total` counts, and the counts of the `modules` and `kinds` of items.
+    pub fn to_json(&self) -> String {
+        let report = Report {
+            format_version: FORMAT_VERSION,
+            name: &self.name,
+            total: Row::new("total", &self.total),
+            modules: self.modules.iter().map(|(name, count)| Row::new(name, count)).collect(),
+            kinds: self.kinds.iter().map(|(name, count)| Row::new(name, count)).collect(),
+        };
+        json::as_json(&report).to_string()
+    }
+
+    /// Counts `item` and the items below it, which are in the module `module`.
+    fn count(&mut self, item: &clean::Item, module: &str) {
+        match item.inner {
+            clean::StrippedItem(..) |
+            clean::ExternCrateItem(..) |
+            clean::ImportItem(..) |
+            clean::PrimitiveItem(..) |
+            clean::DefaultImplItem(..) => return,
+            clean::ImplItem(ref i) => {
+                if i.trait_.is_none() {
+                    for item in &i.items {
+                        self.count(item, module);
+                    }
+                }
+                return;
+            }
+            clean::ModuleItem(ref m) => {
+                // The docs of a module are counted in its own row.
+                let path = match item.name {
+                    Some(ref name) if !m.is_crate => format!("{}::{}", module, name),
+                    _ => module.to_string(),
+                };
+                self.add(item, &path);
+                for item in &m.items {
+                    self.count(item, &path);
+                }
+                return;
+            }
+            _ => {}
+        }
+
+        self.add(item, module);
+        let items = match item.inner {
+            clean::StructItem(ref s) => &s.fields[..],
+            clean::UnionItem(ref u) => &u.fields[..],
+            clean::EnumItem(ref e) => &e.variants[..],
+            clean::VariantItem(clean::Variant { kind: clean::VariantKind::Struct(ref s) }) => {
+                &s.fields[..]
+            }
+            clean::TraitItem(ref t) => &t.items[..],
+            _ => &[],
+        };
+        for item in items {
+            self.count(item, module);
+        }
+    }
+
+    fn add(&mut self, item: &clean::Item, module: &str) {
+        let docs = item.attrs.doc_strings.join("\n");
+        self.total.add(&docs);
+        self.modules.entry(module.to_string()).or_insert_with(ItemCount::default).add(&docs);
+        self.kinds.entry(item.type_().css_class()).or_insert_with(ItemCount::default).add(&docs);
+    }
+}
+
+impl fmt::Display for Coverage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Documentation coverage of `{}`:", self.name)?;
+        let modules = self.modules.iter().map(|(name, count)| (&name[..], *count)).collect();
+        write_table(f, "Module", modules, &self.total)?;
+        let kinds = self.kinds.iter().map(|(name, count)| (*name, *count)).collect();
+        write_table(f, "Kind", kinds, &self.total)
+    }
+}
+
+fn write_table(f: &mut fmt::Formatter, heading: &str, rows: Vec<(&str, ItemCount)>,
+               total: &ItemCount) -> fmt::Result {
+    let width = rows.iter().map(|&(name, _)| name.len())
+                           .chain(Some(heading.len()))
+                           .chain(Some("Total".len()))
+                           .max()
+                           .unwrap_or(0);
+    let dashes: String = iter::repeat('-').take(width).collect();
+    let line = format!("+-{}-+------------+-------+------------+----------+", dashes);
+
+    writeln!(f, "{}", line)?;
+    writeln!(f, "| {:<width$} | Documented | Total | Percentage | Examples |",
+             heading, width = width)?;
+    writeln!(f, "{}", line)?;
+    for &(name, ref count) in &rows {
+        write_row(f, name, count, width)?;
+    }
+    writeln!(f, "{}", line)?;
+    write_row(f, "Total", total, width)?;
+    writeln!(f, "{}", line)
+}
+
+fn write_row(f: &mut fmt::Formatter, name: &str, count: &ItemCount, width: usize) -> fmt::Result {
+    writeln!(f, "| {:<width$} | {:>10} | {:>5} | {:>9.1}% | {:>8} |",
+             name, count.documented, count.total, count.percentage(), count.with_examples,
+             width = width)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::{Coverage, ItemCount};
+
+    #[test]
+    fn table_is_aligned() {
+        let count = ItemCount { total: 4, documented: 3, with_examples: 1 };
+        let mut kinds = BTreeMap::new();
+        kinds.insert("fn", count);
+        let mut modules = BTreeMap::new();
+        modules.insert("a".to_string(), count);
+        let coverage = Coverage {
+            name: "a".to_string(),
+            total: count,
+            modules: modules,
+            kinds: kinds,
+        };
+        let table = coverage.to_string();
+        let lines: Vec<&str> = table.lines().skip(1).collect();
+        assert_eq!(lines.len(), 14, "{}", table);
+        for table_lines in lines.chunks(7) {
+            let width = table_lines[0].len();
+            assert!(table_lines.iter().all(|line| line.len() == width), "{}", table);
+        }
+        // The names of the kinds are shorter than "Total".
+        assert_eq!(lines[12], "| Total |          3 |     4 |      75.0% |        1 |");
+    }
+}
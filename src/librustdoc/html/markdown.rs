@@ -20,7 +20,7 @@
 //! use rustdoc::html::markdown::Markdown;
 //!
 //! let s = "My *markdown* _text_";
-//! let html = format!("{}", Markdown(s));
+//! let html = format!("{}", Markdown(s, &[]));
 //! // ... something using html
 //! ```
 
@@ -42,10 +42,11 @@ use html::highlight;
 use html::escape::Escape;
 use test;
 
-/// A unit struct which has the `fmt::Display` trait implemented. When
+/// A tuple struct which has the `fmt::Display` trait implemented. When
 /// formatted, this struct will emit the HTML corresponding to the rendered
-/// version of the contained markdown string.
-pub struct Markdown<'a>(pub &'a str);
+/// version of the contained markdown string, with the Rust paths used as
+/// links in it replaced by the URLs they are paired with.
+pub struct Markdown<'a>(pub &'a str, pub &'a [(String, String)]);
 /// A unit struct like `Markdown`, that renders the markdown with a
 /// table of contents.
 pub struct MarkdownWithToc<'a>(pub &'a str);
@@ -151,7 +152,9 @@ struct MyOpaque {
     dfltblk: extern "C" fn(*mut hoedown_buffer, *const hoedown_buffer,
                            *const hoedown_buffer, *const hoedown_renderer_data,
                            libc::size_t),
+    dfltlink: linkfn,
     toc_builder: Option<TocBuilder>,
+    links: Vec<(String, String)>,
 }
 
 #[repr(C)]
@@ -229,6 +232,7 @@ thread_local!(pub static PLAYGROUND: RefCell<Option<(Option<String>, String)>> =
 
 pub fn render(w: &mut fmt::Formatter,
               s: &str,
+              links: &[(String, String)],
               print_toc: bool,
               html_flags: libc::c_uint) -> fmt::Result {
     extern fn block(ob: *mut hoedown_buffer, orig_text: *const hoedown_buffer,
@@ -396,18 +400,62 @@ pub fn render(w: &mut fmt::Formatter,
         1
     }
 
+    extern fn link(ob: *mut hoedown_buffer, link: *const hoedown_buffer,
+                   title: *const hoedown_buffer, content: *const hoedown_buffer,
+                   data: *const hoedown_renderer_data, line: libc::size_t) -> libc::c_int {
+        unsafe {
+            let opaque = (*data).opaque as *mut hoedown_html_renderer_state;
+            let my_opaque: &MyOpaque = &*((*opaque).opaque as *const MyOpaque);
+            let url = if link.is_null() {
+                None
+            } else {
+                str::from_utf8((*link).as_bytes()).ok().and_then(|dest| {
+                    my_opaque.links.iter().find(|&&(ref path, _)| path == dest)
+                })
+            };
+            match url {
+                Some(&(_, ref url)) => {
+                    let url_buf = hoedown_buffer_new(DEF_OUNIT);
+                    hoedown_buffer_put(url_buf, url.as_ptr() as *const libc::c_char,
+                                       url.len() as libc::size_t);
+                    let ret = (my_opaque.dfltlink)(ob, url_buf, title, content, data, line);
+                    hoedown_buffer_free(url_buf);
+                    ret
+                }
+                None => (my_opaque.dfltlink)(ob, link, title, content, data, line),
+            }
+        }
+    }
+
+    // Paths in backticks used as shortcut links, like [`Vec::push`], are
+    // made links by defining their references.
+    let with_references;
+    let s = if links.is_empty() {
+        s
+    } else {
+        let mut md = format!("{}\n\n", s);
+        for &(ref path, ref url) in links {
+            md.push_str(&format!("[`{}`]: {}\n", path, url));
+        }
+        with_references = md;
+        &with_references[..]
+    };
+
     unsafe {
         let ob = hoedown_buffer_new(DEF_OUNIT);
         let renderer = hoedown_html_renderer_new(html_flags, 0);
         let mut opaque = MyOpaque {
             dfltblk: (*renderer).blockcode.unwrap(),
-            toc_builder: if print_toc {Some(TocBuilder::new())} else {None}
+            dfltlink: (*renderer).link.unwrap(),
+            toc_builder: if print_toc {Some(TocBuilder::new())} else {None},
+            links: links.to_vec(),
         };
         (*((*renderer).opaque as *mut hoedown_html_renderer_state)).opaque
                 = &mut opaque as *mut _ as *mut libc::c_void;
         (*renderer).blockcode = Some(block);
         (*renderer).header = Some(header);
         (*renderer).codespan = Some(codespan);
+        (*renderer).link = Some(link);
 
         let document = hoedown_document_new(renderer, HOEDOWN_EXTENSIONS, 16);
         hoedown_document_render(document, ob, s.as_ptr(),
@@ -573,17 +621,17 @@ impl LangString {
 
 impl<'a> fmt::Display for Markdown<'a> {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        let Markdown(md) = *self;
+        let Markdown(md, links) = *self;
         // This is actually common enough to special-case
         if md.is_empty() { return Ok(()) }
-        render(fmt, md, false, 0)
+        render(fmt, md, links, false, 0)
     }
 }
 
 impl<'a> fmt::Display for MarkdownWithToc<'a> {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         let MarkdownWithToc(md) = *self;
-        render(fmt, md, true, 0)
+        render(fmt, md, &[], true, 0)
     }
 }
 
@@ -592,7 +640,7 @@ impl<'a> fmt::Display for MarkdownHtml<'a> {
         let MarkdownHtml(md) = *self;
         // This is actually common enough to special-case
         if md.is_empty() { return Ok(()) }
-        render(fmt, md, false, HOEDOWN_HTML_ESCAPE)
+        render(fmt, md, &[], false, HOEDOWN_HTML_ESCAPE)
     }
 }
 
@@ -645,10 +693,111 @@ pub fn plain_summary_line(md: &str) -> String {
     }
 }
 
+/// Whether `md` has a fenced code block which is tested as Rust code, i.e.
+/// an example.
+pub fn has_rust_code_block(md: &str) -> bool {
+    let mut in_code_block = false;
+    for line in md.lines() {
+        let line = line.trim_left();
+        if !line.starts_with("```") && !line.starts_with("~~~") {
+            continue;
+        }
+        if !in_code_block && LangString::parse(line[3..].trim()).rust {
+            return true;
+        }
+        in_code_block = !in_code_block;
+    }
+    false
+}
+
+/// Finds the Rust paths used as links in `md`: the destinations of links and
+/// reference definitions which are paths, and the paths in backticks used as
+/// shortcut links, like [`Vec::push`].
+pub fn rust_links(md: &str) -> Vec<String> {
+    let mut paths = vec![];
+    let mut in_code_block = false;
+    for line in md.lines() {
+        let line = line.trim_left();
+        if line.starts_with("```") || line.starts_with("~~~") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            continue;
+        }
+
+        // A reference definition, `[label]: destination`.
+        if line.starts_with('[') {
+            if let Some(end) = line.find("]:") {
+                if let Some(dest) = line[end + 2..].split_whitespace().next() {
+                    if is_rust_path_destination(dest) {
+                        paths.push(dest.to_string());
+                    }
+                }
+                continue;
+            }
+        }
+
+        let mut rest = line;
+        while let Some(start) = rest.find('[') {
+            rest = &rest[start + 1..];
+            let end = match rest.find(']') {
+                Some(end) => end,
+                None => break,
+            };
+            let (text, after) = (&rest[..end], &rest[end + 1..]);
+            if after.starts_with('(') {
+                // An inline link, `[text](destination)`.
+                let dest = after[1..].split(|c: char| c == ')' || c.is_whitespace()).next();
+                if let Some(dest) = dest {
+                    if is_rust_path_destination(dest) {
+                        paths.push(dest.to_string());
+                    }
+                }
+            } else if !after.starts_with('[') && !after.starts_with(':') &&
+                      text.len() > 2 && text.starts_with('`') && text.ends_with('`') {
+                let path = &text[1..text.len() - 1];
+                if is_rust_path(path) {
+                    paths.push(path.to_string());
+                }
+            }
+            rest = after;
+        }
+    }
+    paths
+}
+
+/// Whether `s` is a path like `Vec::push`, `::std::vec` or `String::new()`,
+/// which may name its namespace like `type@std::vec` or `vec!`.
+fn is_rust_path(s: &str) -> bool {
+    let s = ["type@", "value@", "macro@"].iter().find(|prefix| s.starts_with(*prefix))
+                                          .map_or(s, |prefix| &s[prefix.len()..]);
+    let s = if s.ends_with('!') { &s[..s.len() - 1] } else { s.trim_right_matches("()") };
+    let s = if s.starts_with("::") { &s[2..] } else { s };
+    !s.is_empty() && s.split("::").all(|segment| {
+        let mut chars = segment.chars();
+        match chars.next() {
+            Some(c) if c.is_alphabetic() || c == '_' => {
+                chars.all(|c| c.is_alphanumeric() || c == '_')
+            }
+            _ => false,
+        }
+    })
+}
+
+/// Whether the link destination `s` is a Rust path rather than a URL. Unlike
+/// in backticks, a single identifier like `LICENSE` is taken for a relative
+/// URL, so a path needs a `::`, a namespace or a `()` or `!` suffix.
+fn is_rust_path_destination(s: &str) -> bool {
+    is_rust_path(s) &&
+        (s.contains("::") || s.ends_with("()") || s.ends_with('!') ||
+         ["type@", "value@", "macro@"].iter().any(|prefix| s.starts_with(*prefix)))
+}
+
 #[cfg(test)]
 mod tests {
     use super::{LangString, Markdown, MarkdownHtml};
-    use super::plain_summary_line;
+    use super::{has_rust_code_block, plain_summary_line, rust_links};
     use html::render::reset_ids;
 
     #[test]
@@ -687,14 +836,14 @@ mod tests {
     #[test]
     fn issue_17736() {
         let markdown = "# title";
-        format!("{}", Markdown(markdown));
+        format!("{}", Markdown(markdown, &[]));
         reset_ids(true);
     }
 
     #[test]
     fn test_header() {
         fn t(input: &str, expect: &str) {
-            let output = format!("{}", Markdown(input));
+            let output = format!("{}", Markdown(input, &[]));
             assert_eq!(output, expect);
             reset_ids(true);
         }
@@ -716,7 +865,7 @@ mod tests {
     #[test]
     fn test_header_ids_multiple_blocks() {
         fn t(input: &str, expect: &str) {
-            let output = format!("{}", Markdown(input));
+            let output = format!("{}", Markdown(input, &[]));
             assert_eq!(output, expect);
         }
 
@@ -763,4 +912,51 @@ mod tests {
         t("`Struct<'a, T>`", "<p><code>Struct&lt;&#39;a, T&gt;</code></p>\n");
         t("Struct<'a, T>", "<p>Struct&lt;&#39;a, T&gt;</p>\n");
     }
+
+    #[test]
+    fn test_rust_links() {
+        let md = "Calls [`Vec::push`] and [`String::new()`], see [`Option`][opt] and\n\
+                  [the module](::std::vec), [the book](book/index.html) or [`a`].\n\
+                  Uses [`vec!`], [the type](type@foo), [`value@foo`] but not [`a@b`].\n\
+                  Neither [the license](LICENSE) nor [the guide](book) are paths,\n\
+                  but [the macro](format!) is.\n\
+                  \n\
+                  ```\n\
+                  let x = [`Ignored`];\n\
+                  ```\n\
+                  \n\
+                  [opt]: std::option::Option\n\
+                  [run]: run()\n\
+                  [readme]: README\n\
+                  [home]: https://www.rust-lang.org\n";
+        assert_eq!(rust_links(md), vec!["Vec::push", "String::new()", "::std::vec", "a",
+                                        "vec!", "type@foo", "value@foo", "format!",
+                                        "std::option::Option", "run()"]);
+    }
+
+    #[test]
+    fn test_has_rust_code_block() {
+        assert!(!has_rust_code_block(""));
+        assert!(!has_rust_code_block("No examples, only `code()`."));
+        assert!(has_rust_code_block("```\nfoo();\n```"));
+        assert!(has_rust_code_block("Example:\n\n  ```rust,no_run\n  foo();\n  ```\n"));
+        assert!(has_rust_code_block("~~~should_panic\nfoo();\n~~~"));
+        assert!(!has_rust_code_block("```text\nfoo();\n```"));
+        assert!(!has_rust_code_block("```sh\n$ foo\n```"));
+        // Indented code blocks aren't tested.
+        assert!(!has_rust_code_block("Example:\n\n    foo();\n"));
+        assert!(has_rust_code_block("```text\nfoo\n```\n\n```\nfoo();\n```"));
+        // Unterminated fences still open a code block.
+        assert!(has_rust_code_block("```\nfoo();"));
+    }
+
+    #[test]
+    fn test_markdown_links() {
+        let url = "vec/struct.Vec.html#method.push";
+        let links = vec![("Vec::push".to_string(), url.to_string())];
+        let output = format!("{}", Markdown("See [`Vec::push`] or [push](Vec::push).", &links));
+        assert!(output.contains(&format!("<a href=\"{}\"><code>Vec::push</code></a>", url)));
+        assert!(output.contains(&format!("<a href=\"{}\">push</a>", url)));
+        reset_ids(true);
+    }
 }
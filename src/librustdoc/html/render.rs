@@ -32,6 +32,10 @@
 //! for creating the corresponding search index and source file renderings.
 //! These threads are not parallelized (they haven't been a bottleneck yet), and
 //! both occur before the crate is rendered.
+//!
+//! Once all pages are written, the relative links in the docs are checked
+//! against them, warning about links to pages which don't exist and about
+//! `#anchor` fragments naming no element of their page.
 pub use self::ExternalLocation::*;
 
 use std::ascii::AsciiExt;
@@ -47,14 +51,18 @@ use std::io::{self, BufWriter, BufReader};
 use std::iter::repeat;
 use std::mem;
 use std::path::{PathBuf, Path, Component};
+use std::rc::Rc;
 use std::str;
 use std::sync::Arc;
 
 use externalfiles::ExternalHtml;
 
+use errors;
 use serialize::json::{ToJson, Json, as_json};
 use syntax::{abi, ast};
+use syntax::codemap::CodeMap;
 use syntax::feature_gate::UnstableFeatures;
+use syntax_pos::Span;
 use rustc::hir::def_id::{CrateNum, CRATE_DEF_INDEX, DefId};
 use rustc::middle::privacy::AccessLevels;
 use rustc::middle::stability;
@@ -63,6 +71,7 @@ use rustc::util::nodemap::{FxHashMap, FxHashSet};
 use rustc_data_structures::flock;
 
 use clean::{self, AttributesExt, GetDefId, SelfTy, Mutability};
+use core;
 use doctree;
 use fold::DocFolder;
 use html::escape::Escape;
@@ -278,6 +287,8 @@ pub struct RenderInfo {
     pub external_typarams: FxHashMap<DefId, String>,
     pub deref_trait_did: Option<DefId>,
     pub deref_mut_trait_did: Option<DefId>,
+    /// The codemap of the crate, to point at the docs with broken links.
+    pub codemap: Option<Rc<CodeMap>>,
 }
 
 /// Helper struct to render all source code to HTML pages
@@ -376,6 +387,22 @@ thread_local!(pub static CURRENT_LOCATION_KEY: RefCell<Vec<String>> =
                     RefCell::new(Vec::new()));
 thread_local!(static USED_ID_MAP: RefCell<FxHashMap<String, usize>> =
                     RefCell::new(init_ids()));
+thread_local!(static LINK_CHECK: RefCell<LinkCheck> = Default::default());
+
+/// The pages written and the relative links in the docs on them, which are
+/// checked once the whole crate is rendered.
+#[derive(Default)]
+struct LinkCheck {
+    /// Whether links are checked, with `--check-links`.
+    enabled: bool,
+    /// The page being rendered, relative to the root of the output.
+    current_page: String,
+    /// The ids of the elements of each page written, or `None` for redirects.
+    pages: FxHashMap<String, Option<FxHashSet<String>>>,
+    /// The links, with the page they're on and the span of their docs.
+    links: Vec<(String, String, Option<Span>)>,
+    seen: FxHashSet<(String, String, Option<Span>)>,
+}
 
 fn init_ids() -> FxHashMap<String, usize> {
     [
@@ -432,7 +459,8 @@ pub fn run(mut krate: clean::Crate,
            dst: PathBuf,
            passes: FxHashSet<String>,
            css_file_extension: Option<PathBuf>,
-           renderinfo: RenderInfo) -> Result<(), Error> {
+           renderinfo: RenderInfo,
+           check_links: bool) -> Result<(), Error> {
     let src_root = match krate.src.parent() {
         Some(p) => p.to_path_buf(),
         None => PathBuf::new(),
@@ -503,6 +531,7 @@ pub fn run(mut krate: clean::Crate,
         external_typarams,
         deref_trait_did,
         deref_mut_trait_did,
+        codemap,
     } = renderinfo;
 
     let external_paths = external_paths.into_iter()
@@ -570,7 +599,187 @@ pub fn run(mut krate: clean::Crate,
     write_shared(&cx, &krate, &*cache, index)?;
 
     // And finally render the whole crate's documentation
-    cx.krate(krate)
+    let dst = cx.dst.clone();
+    let name = krate.name.clone();
+    LINK_CHECK.with(|check| {
+        *check.borrow_mut() = LinkCheck { enabled: check_links, ..LinkCheck::default() }
+    });
+    cx.krate(krate)?;
+
+    if check_links {
+        let handler = core::new_handler(codemap.map(|cm| cm as Rc<errors::CodeMapper>));
+        check_broken_links(&dst, &name, &handler);
+    }
+    Ok(())
+}
+
+/// Warns about the links recorded while rendering which point to a page of
+/// the crate which wasn't written, or to an anchor missing from their page,
+/// for `--check-links`.
+///
+/// Links leaving the directory of the crate are checked against the pages
+/// of other crates if those already exist, and are otherwise left alone as
+/// the other crates may be documented later on.
+fn check_broken_links(dst: &Path, krate: &str, handler: &errors::Handler) {
+    let LinkCheck { mut pages, links, .. } =
+        LINK_CHECK.with(|check| mem::replace(&mut *check.borrow_mut(), LinkCheck::default()));
+    let crate_dir = format!("{}/", krate);
+    for &(ref page, ref link, span) in &links {
+        let (path, fragment) = match link.find('#') {
+            Some(i) => (&link[..i], &link[i + 1..]),
+            None => (&link[..], ""),
+        };
+        let path = match path.find('?') {
+            Some(i) => &path[..i],
+            None => path,
+        };
+        let mut target = if path.is_empty() {
+            page.clone()
+        } else {
+            match resolve_relative_link(page, path) {
+                Some(target) => target,
+                None => continue,
+            }
+        };
+        if !pages.contains_key(&target) {
+            if dst.join(&target).is_dir() {
+                target.push_str("/index.html");
+            }
+            // A page written before, e.g. when documenting another crate.
+            if dst.join(&target).is_file() {
+                pages.insert(target.clone(), written_page_ids(&dst.join(&target)));
+            }
+        }
+
+        let ids = match pages.get(&target) {
+            Some(ids) => ids.as_ref(),
+            None if !target.starts_with(&crate_dir) => None,
+            None => {
+                let msg = format!("broken link to `{}`: the page `{}` doesn't exist",
+                                  link, target);
+                warn_about_link(handler, span, page, &msg);
+                continue;
+            }
+        };
+        if let Some(ids) = ids {
+            if !fragment.is_empty() && !ids.contains(fragment) {
+                let msg = format!("dead anchor in link to `{}`: the page `{}` has no element \
+                                   with the id `{}`",
+                                  link, target, fragment);
+                warn_about_link(handler, span, page, &msg);
+            }
+        }
+    }
+}
+
+fn warn_about_link(handler: &errors::Handler, span: Option<Span>, page: &str, msg: &str) {
+    let mut diag = match span {
+        Some(span) => handler.struct_span_warn(span, msg),
+        None => handler.struct_warn(msg),
+    };
+    diag.note(&format!("the link is in the docs on the page `{}`", page));
+    diag.emit();
+}
+
+/// The ids of the elements of the page at `path`, which wasn't written while
+/// rendering this crate, or `None` if it's a redirect or not an HTML page.
+fn written_page_ids(path: &Path) -> Option<FxHashSet<String>> {
+    if path.extension().map_or(true, |ext| ext != "html") {
+        return None;
+    }
+    let mut html = String::new();
+    if File::open(path).and_then(|mut f| f.read_to_string(&mut html)).is_err() ||
+       html.contains("<meta http-equiv=\"refresh\"") {
+        return None;
+    }
+    Some(attribute_values(&html, "id").into_iter().collect())
+}
+
+/// Resolves `path`, linked to from `page`, to a path relative to the root of
+/// the output, or `None` if it leaves the output or isn't relative at all.
+fn resolve_relative_link(page: &str, path: &str) -> Option<String> {
+    // A path on the server, or a URL with a scheme like `https:` or `mailto:`.
+    if path.starts_with('/') || path.split('/').next().unwrap().contains(':') {
+        return None;
+    }
+    let mut parts: Vec<&str> = page.split('/').collect();
+    parts.pop();
+    for part in path.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                if parts.pop().is_none() {
+                    return None;
+                }
+            }
+            _ => parts.push(part),
+        }
+    }
+    let mut target = parts.join("/");
+    if path.ends_with('/') || path.ends_with("/.") || path.ends_with("..") || path == "." {
+        if !target.is_empty() {
+            target.push('/');
+        }
+        target.push_str("index.html");
+    }
+    Some(target)
+}
+
+/// Records the page at `path`, relative to the root of the output, with the
+/// ids of its elements unless it's a redirect.
+fn record_page(path: String, html: Option<&[u8]>) {
+    LINK_CHECK.with(|check| {
+        let mut check = check.borrow_mut();
+        if check.enabled {
+            let ids = html.map(|html| {
+                attribute_values(&String::from_utf8_lossy(html), "id").into_iter().collect()
+            });
+            check.pages.insert(path, ids);
+        }
+    });
+}
+
+/// Records the relative links in `html`, the rendered docs of `item` on the
+/// current page, to be checked once all pages are written.
+fn record_links(item: &clean::Item, html: &str) {
+    LINK_CHECK.with(|check| {
+        let mut check = check.borrow_mut();
+        if !check.enabled {
+            return;
+        }
+        for link in attribute_values(html, "href") {
+            let key = (check.current_page.clone(), link, item.attrs.span);
+            if check.seen.insert(key.clone()) {
+                check.links.push(key);
+            }
+        }
+    });
+}
+
+/// The unescaped values of the attributes named `name` in `html`.
+fn attribute_values(html: &str, name: &str) -> Vec<String> {
+    let mut values = vec![];
+    let mut rest = html;
+    while let Some(i) = rest.find(name) {
+        let before = &rest[..i];
+        rest = &rest[i + name.len()..];
+        if !before.ends_with(|c: char| c.is_whitespace()) || !rest.starts_with('=') {
+            continue;
+        }
+        let quote = match rest[1..].chars().next() {
+            Some(quote @ '"') | Some(quote @ '\'') => quote,
+            _ => continue,
+        };
+        rest = &rest[2..];
+        let end = rest.find(quote).unwrap_or(rest.len());
+        values.push(rest[..end].replace("&quot;", "\"")
+                               .replace("&#39;", "'")
+                               .replace("&lt;", "<")
+                               .replace("&gt;", ">")
+                               .replace("&amp;", "&"));
+        rest = &rest[end..];
+    }
+    values
 }
 
 /// Build the search index from the collected metadata
@@ -1218,6 +1427,17 @@ impl Context {
         repeat("../").take(self.current.len()).collect::<String>()
     }
 
+    /// The path of the page `file_name` in the current directory, relative to
+    /// the root of the output.
+    fn page_path(&self, file_name: &str) -> String {
+        let mut path = self.current.join("/");
+        if !path.is_empty() {
+            path.push('/');
+        }
+        path.push_str(file_name);
+        path
+    }
+
     /// Recurse in the directory structure and change the "root path" to make
     /// sure it always points to the top (relatively).
     fn recurse<T, F>(&mut self, s: String, f: F) -> T where
@@ -1307,6 +1527,12 @@ impl Context {
         };
 
         reset_ids(true);
+        let file_name = if pushname {
+            item_path(it.type_(), it.name.as_ref().unwrap())
+        } else {
+            "index.html".to_string()
+        };
+        LINK_CHECK.with(|check| check.borrow_mut().current_page = self.page_path(&file_name));
 
         if !self.render_redirect_pages {
             layout::render(writer, &self.shared.layout, &page,
@@ -1362,6 +1588,8 @@ impl Context {
                     try_err!(fs::create_dir_all(&this.dst), &this.dst);
                     let mut dst = try_err!(File::create(&joint_dst), &joint_dst);
                     try_err!(dst.write_all(&buf), &joint_dst);
+                    let html = if this.render_redirect_pages { None } else { Some(&buf[..]) };
+                    record_page(this.page_path("index.html"), html);
                 }
 
                 let m = match item.inner {
@@ -1397,15 +1625,18 @@ impl Context {
                 try_err!(fs::create_dir_all(&self.dst), &self.dst);
                 let mut dst = try_err!(File::create(&joint_dst), &joint_dst);
                 try_err!(dst.write_all(&buf), &joint_dst);
+                let html = if self.render_redirect_pages { None } else { Some(&buf[..]) };
+                record_page(self.page_path(file_name), html);
 
                 // Redirect from a sane URL using the namespace to Rustdoc's
                 // URL for the page.
                 let redir_name = format!("{}.{}.html", name, item_type.name_space());
-                let redir_dst = self.dst.join(redir_name);
+                let redir_dst = self.dst.join(&redir_name);
                 if let Ok(mut redirect_out) = OpenOptions::new().create_new(true)
                                                                 .write(true)
                                                                 .open(&redir_dst) {
                     try_err!(layout::redirect(&mut redirect_out, file_name), &redir_dst);
+                    record_page(self.page_path(&redir_name), None);
                 }
 
                 // If the item is a macro, redirect from the old macro URL (with !)
@@ -1413,9 +1644,10 @@ impl Context {
                 // FIXME(#35705) remove this redirect.
                 if item_type == ItemType::Macro {
                     let redir_name = format!("{}.{}!.html", item_type, name);
-                    let redir_dst = self.dst.join(redir_name);
+                    let redir_dst = self.dst.join(&redir_name);
                     let mut redirect_out = try_err!(File::create(&redir_dst), &redir_dst);
                     try_err!(layout::redirect(&mut redirect_out, file_name), &redir_dst);
+                    record_page(self.page_path(&redir_name), None);
                 }
             }
         }
@@ -1649,18 +1881,33 @@ fn document_short(w: &mut fmt::Formatter, item: &clean::Item, link: AssocItemLin
         } else {
             format!("{}", &plain_summary_line(Some(s)))
         };
-        write!(w, "<div class='docblock'>{}</div>", Markdown(&markdown))?;
+        write!(w, "<div class='docblock'>{}</div>", Markdown(&markdown, &doc_links(item)))?;
     }
     Ok(())
 }
 
 fn document_full(w: &mut fmt::Formatter, item: &clean::Item) -> fmt::Result {
     if let Some(s) = item.doc_value() {
-        write!(w, "<div class='docblock'>{}</div>", Markdown(s))?;
+        let docs = Markdown(s, &doc_links(item)).to_string();
+        record_links(item, &docs);
+        write!(w, "<div class='docblock'>{}</div>", docs)?;
     }
     Ok(())
 }
 
+/// The URLs of the Rust paths used as links in the docs of `item`.
+fn doc_links(item: &clean::Item) -> Vec<(String, String)> {
+    item.attrs.links.iter().filter_map(|&(ref path, did, ref fragment)| {
+        href(did).map(|(mut url, ..)| {
+            if let Some(ref fragment) = *fragment {
+                url.push('#');
+                url.push_str(fragment);
+            }
+            (path.clone(), url)
+        })
+    }).collect()
+}
+
 fn document_stability(w: &mut fmt::Formatter, cx: &Context, item: &clean::Item) -> fmt::Result {
     let stabilities = short_stability(item, cx, true);
     if !stabilities.is_empty() {
@@ -1815,6 +2062,8 @@ fn item_module(w: &mut fmt::Formatter, cx: &Context,
                 };
 
                 let doc_value = myitem.doc_value().unwrap_or("");
+                let docs = shorter(Some(&Markdown(doc_value, &doc_links(myitem)).to_string()));
+                record_links(myitem, &docs);
                 write!(w, "
                        <tr class='{stab} module-item'>
                            <td><a class='{class}' href='{href}'
@@ -1825,7 +2074,7 @@ fn item_module(w: &mut fmt::Formatter, cx: &Context,
                        </tr>",
                        name = *myitem.name.as_ref().unwrap(),
                        stab_docs = stab_docs,
-                       docs = shorter(Some(&Markdown(doc_value).to_string())),
+                       docs = docs,
                        class = myitem.type_(),
                        stab = myitem.stability_class(),
                        unsafety_flag = unsafety_flag,
@@ -2829,7 +3078,9 @@ fn render_impl(w: &mut fmt::Formatter, cx: &Context, i: &Impl, link: AssocItemLi
         write!(w, "</span>")?;
         write!(w, "</h3>\n")?;
         if let Some(ref dox) = i.impl_item.doc_value() {
-            write!(w, "<div class='docblock'>{}</div>", Markdown(dox))?;
+            let docs = Markdown(dox, &doc_links(&i.impl_item)).to_string();
+            record_links(&i.impl_item, &docs);
+            write!(w, "<div class='docblock'>{}</div>", docs)?;
         }
     }
 
@@ -3143,3 +3394,38 @@ fn test_unique_id() {
     reset_ids(true);
     test();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{attribute_values, resolve_relative_link};
+
+    #[test]
+    fn test_resolve_relative_link() {
+        let t = |path| resolve_relative_link("foo/struct.Foo.html", path);
+        assert_eq!(t("struct.Bar.html"), Some("foo/struct.Bar.html".to_string()));
+        assert_eq!(t("./bar/fn.baz.html"), Some("foo/bar/fn.baz.html".to_string()));
+        assert_eq!(t("../bar/index.html"), Some("bar/index.html".to_string()));
+        assert_eq!(t("bar/../struct.Foo.html"), Some("foo/struct.Foo.html".to_string()));
+        assert_eq!(t("bar/"), Some("foo/bar/index.html".to_string()));
+        assert_eq!(t("."), Some("foo/index.html".to_string()));
+        assert_eq!(t(".."), Some("index.html".to_string()));
+        // Links leaving the output or which aren't relative.
+        assert_eq!(t("../../index.html"), None);
+        assert_eq!(t("/index.html"), None);
+        assert_eq!(t("https://example.com/index.html"), None);
+        assert_eq!(t("mailto:someone@example.com"), None);
+    }
+
+    #[test]
+    fn test_attribute_values() {
+        let html = "<a href=\"a.html\" id='x'>a</a>\n\
+                    <a\nhref=\"b.html#c&amp;d\">b</a>\n\
+                    <a data-href=\"no\" href=no>c</a>\n\
+                    <p>href=\"no\"</p>\n\
+                    <a title=\"&lt;&quot;&#39;&gt;\" href=\"e.html";
+        assert_eq!(attribute_values(html, "href"), vec!["a.html", "b.html#c&d", "e.html"]);
+        assert_eq!(attribute_values(html, "id"), vec!["x"]);
+        assert_eq!(attribute_values(html, "title"), vec!["<\"'>"]);
+        assert!(attribute_values(html, "class").is_empty());
+    }
+}
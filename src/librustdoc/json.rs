@@ -0,0 +1,148 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The JSON output format, `--output-format json`.
+//!
+//! This writes the cleaned crate to `<crate name>.json` in the output
+//! directory, for tools that need the public API of a crate without scraping
+//! the HTML. The top-level object has these fields:
+//!
+//! * `format_version`: `FORMAT_VERSION`, which is increased whenever the
+//!   layout of the output changes.
+//! * `name`: the name of the crate.
+//! * `module`: the crate's root module, as the `clean::Item` tree with the
+//!   docs, attributes, source span, visibility, stability and deprecation of
+//!   each item, after the passes have run.
+//! * `externs`: the crates it depends on, as objects with the crate number
+//!   used in the `krate` field of ids and the crate itself.
+//! * `primitives`: the primitive types documented by the crate.
+//! * `external_traits`: the traits of other crates it implements or uses,
+//!   keyed by their ids.
+//! * `implementors`: every trait implementation in the crate, as the id of
+//!   the `impl` item, the trait and the implementing type.
+//! * `passes`: the passes which were run on the crate.
+//!
+//! Below the top level, the clean types are written the way `libserialize`
+//! encodes them: structs as objects, and enums as objects with a `variant`
+//! name and their `fields` in an array, or as a string for unit variants.
+
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use rustc::hir::def_id::DefId;
+use serialize::json;
+
+use clean;
+
+/// The version of the layout of the output.
+pub const FORMAT_VERSION: u32 = 2;
+
+#[derive(RustcEncodable)]
+struct Crate<'a> {
+    format_version: u32,
+    name: &'a str,
+    module: &'a Option<clean::Item>,
+    externs: Vec<ExternCrate<'a>>,
+    primitives: Vec<Primitive<'a>>,
+    external_traits: Vec<ExternalTrait<'a>>,
+    implementors: Vec<Implementor<'a>>,
+    passes: &'a [String],
+}
+
+#[derive(RustcEncodable)]
+struct ExternCrate<'a> {
+    krate: u32,
+    name: &'a str,
+    primitives: Vec<Primitive<'a>>,
+}
+
+#[derive(RustcEncodable)]
+struct Primitive<'a> {
+    id: DefId,
+    primitive: clean::PrimitiveType,
+    attrs: &'a clean::Attributes,
+}
+
+#[derive(RustcEncodable)]
+struct ExternalTrait<'a> {
+    id: DefId,
+    inner: &'a clean::Trait,
+}
+
+#[derive(RustcEncodable)]
+struct Implementor<'a> {
+    id: DefId,
+    trait_: &'a clean::Type,
+    for_: &'a clean::Type,
+}
+
+/// Writes the JSON description of `krate` to `dst`, returning the path of
+/// the file written.
+pub fn run(krate: &clean::Crate, dst: &Path, passes: &[String]) -> io::Result<PathBuf> {
+    let mut external_traits: Vec<_> = krate.external_traits.iter().map(|(&id, t)| {
+        ExternalTrait { id: id, inner: t }
+    }).collect();
+    external_traits.sort_by_key(|t| t.id);
+
+    let mut implementors = vec![];
+    if let Some(ref module) = krate.module {
+        collect_implementors(module, &mut implementors);
+    }
+
+    let output = Crate {
+        format_version: FORMAT_VERSION,
+        name: &krate.name,
+        module: &krate.module,
+        externs: krate.externs.iter().map(|&(cnum, ref e)| {
+            ExternCrate {
+                krate: cnum.as_u32(),
+                name: &e.name,
+                primitives: primitives(&e.primitives),
+            }
+        }).collect(),
+        primitives: primitives(&krate.primitives),
+        external_traits: external_traits,
+        implementors: implementors,
+        passes: passes,
+    };
+
+    fs::create_dir_all(dst)?;
+    let path = dst.join(format!("{}.json", krate.name));
+    let mut file = File::create(&path)?;
+    write!(file, "{}", json::as_json(&output))?;
+    Ok(path)
+}
+
+fn primitives(prims: &[(DefId, clean::PrimitiveType, clean::Attributes)]) -> Vec<Primitive> {
+    prims.iter().map(|&(id, primitive, ref attrs)| {
+        Primitive { id: id, primitive: primitive, attrs: attrs }
+    }).collect()
+}
+
+/// Collects the trait implementations in `item` and the modules below it,
+/// leaving out those which were stripped.
+fn collect_implementors<'a>(item: &'a clean::Item, implementors: &mut Vec<Implementor<'a>>) {
+    match item.inner {
+        clean::ModuleItem(ref m) => {
+            for item in &m.items {
+                collect_implementors(item, implementors);
+            }
+        }
+        clean::ImplItem(clean::Impl { trait_: Some(ref trait_), ref for_, .. }) => {
+            implementors.push(Implementor {
+                id: item.def_id,
+                trait_: trait_,
+                for_: for_,
+            });
+        }
+        _ => {}
+    }
+}
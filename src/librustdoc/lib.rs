@@ -56,7 +56,7 @@ use std::env;
 use std::fmt::Display;
 use std::io;
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process;
 use std::sync::mpsc::channel;
 
@@ -68,10 +68,13 @@ use rustc::session::config::{ErrorOutputType, RustcOptGroup, nightly_options,
 #[macro_use]
 pub mod externalfiles;
 
+pub mod api_diff;
 pub mod clean;
 pub mod core;
+pub mod coverage;
 pub mod doctree;
 pub mod fold;
+pub mod json;
 pub mod html {
     pub mod highlight;
     pub mod escape;
@@ -118,7 +121,7 @@ pub fn opts() -> Vec<RustcOptGroup> {
         stable(optopt("r", "input-format", "the input type of the specified file",
                       "[rust]")),
         stable(optopt("w", "output-format", "the output type to write",
-                      "[html]")),
+                      "[html|json]")),
         stable(optopt("o", "output", "where to place the output", "PATH")),
         stable(optopt("", "crate-name", "specify the name of this crate", "NAME")),
         stable(optmulti("L", "library-path", "directory to add to crate search path",
@@ -166,6 +169,20 @@ pub fn opts() -> Vec<RustcOptGroup> {
                         "URL to send code snippets to, may be reset by --markdown-playground-url \
                          or `#![doc(html_playground_url=...)]`",
                         "URL")),
+        unstable(optopt("", "api-diff",
+                        "compare the public API described by the JSON input with that of an \
+                         older version described by OLD, both written by `--output-format json`",
+                        "OLD")),
+        unstable(optflag("", "show-coverage",
+                         "print how many of the public items are documented or have examples \
+                          instead of documenting the crate, as JSON with `--output-format json`")),
+        unstable(optopt("", "coverage-threshold",
+                        "with --show-coverage, fail if less than PERCENT of the public items are \
+                         documented",
+                        "PERCENT")),
+        unstable(optflag("", "check-links",
+                         "warn about relative links in the docs to pages or anchors which don't \
+                          exist")),
     ]
 }
 
@@ -222,6 +239,12 @@ pub fn main_args(args: &[String]) -> isize {
     }
     let input = &matches.free[0];
 
+    if let Some(old) = matches.opt_str("api-diff") {
+        let output_format = matches.opt_str("w");
+        return api_diff::run(Path::new(&old), Path::new(input),
+                             output_format.as_ref().map(|s| &**s));
+    }
+
     let mut libs = SearchPaths::new();
     for s in &matches.opt_strs("L") {
         libs.add_path(s, ErrorOutputType::default());
@@ -282,9 +305,35 @@ pub fn main_args(args: &[String]) -> isize {
         (false, false) => {}
     }
 
+    let show_coverage = matches.opt_present("show-coverage");
+    let check_links = matches.opt_present("check-links");
+    let coverage_threshold = match matches.opt_str("coverage-threshold") {
+        Some(_) if !show_coverage => {
+            print_error("--coverage-threshold requires --show-coverage");
+            return 1;
+        }
+        Some(s) => match s.parse::<f64>() {
+            Ok(threshold) => Some(threshold),
+            Err(_) => {
+                print_error(format!("invalid coverage threshold: {}", s));
+                return 1;
+            }
+        },
+        None => None,
+    };
+
     let output_format = matches.opt_str("w");
+    if output_format.as_ref().map(|s| &**s) == Some("json") &&
+       !nightly_options::is_unstable_enabled(&matches) {
+        print_error("the json output format is unstable and requires `-Z unstable-options`");
+        return 1;
+    }
     let res = acquire_input(input, externs, &matches, move |out| {
         let Output { krate, passes, renderinfo } = out;
+        if show_coverage {
+            return show_doc_coverage(&krate, output_format.as_ref().map(|s| &**s),
+                                     coverage_threshold);
+        }
         info!("going to format");
         match output_format.as_ref().map(|s| &**s) {
             Some("html") | None => {
@@ -292,10 +341,22 @@ pub fn main_args(args: &[String]) -> isize {
                                   output.unwrap_or(PathBuf::from("doc")),
                                   passes.into_iter().collect(),
                                   css_file_extension,
-                                  renderinfo)
+                                  renderinfo,
+                                  check_links)
                     .expect("failed to generate documentation");
                 0
             }
+            Some("json") => {
+                let dst = output.unwrap_or(PathBuf::from("doc"));
+                match json::run(&krate, &dst, &passes) {
+                    Ok(_) => 0,
+                    Err(e) => {
+                        print_error(format!("failed to write json output to `{}`: {}",
+                                            dst.display(), e));
+                        1
+                    }
+                }
+            }
             Some(s) => {
                 print_error(format!("unknown output format: {}", s));
                 1
@@ -308,6 +369,31 @@ pub fn main_args(args: &[String]) -> isize {
     })
 }
 
+/// Prints the documentation coverage of `krate` for `--show-coverage`, failing
+/// if it's below `threshold`.
+fn show_doc_coverage(krate: &clean::Crate, output_format: Option<&str>,
+                     threshold: Option<f64>) -> isize {
+    let coverage = coverage::Coverage::of(krate);
+    match output_format {
+        None => print!("{}", coverage),
+        Some("json") => println!("{}", coverage.to_json()),
+        Some(s) => {
+            print_error(format!("unknown output format for --show-coverage: {}", s));
+            return 1;
+        }
+    }
+    match threshold {
+        Some(threshold) if coverage.total.percentage() < threshold => {
+            let _ = writeln!(&mut io::stderr(),
+                             "rustdoc: documentation coverage of {:.1}% is below the threshold \
+                              of {}%",
+                             coverage.total.percentage(), threshold);
+            1
+        }
+        _ => 0,
+    }
+}
+
 /// Prints an uniformised error message on the standard error output
 fn print_error<T>(error_message: T) where T: Display {
     writeln!(
@@ -328,6 +328,9 @@ declare_features! (
 
     // `extern "msp430-interrupt" fn()`
     (active, abi_msp430_interrupt, "1.16.0", Some(38487)),
+
+    // Allows #![cyclomatic_complexity_limit] and #![fn_statement_limit]
+    (active, fn_complexity_limits, "1.17.0", None),
 );
 
 declare_features! (
@@ -762,6 +765,16 @@ pub const BUILTIN_ATTRIBUTES: &'static [(&'static str, AttributeType, AttributeG
     ("no_builtins", CrateLevel, Ungated),
     ("recursion_limit", CrateLevel, Ungated),
     ("type_length_limit", CrateLevel, Ungated),
+    ("cyclomatic_complexity_limit", CrateLevel, Gated(Stability::Unstable,
+                                                      "fn_complexity_limits",
+                                                      "the complexity limits of functions \
+                                                       are experimental",
+                                                      cfg_fn!(fn_complexity_limits))),
+    ("fn_statement_limit", CrateLevel, Gated(Stability::Unstable,
+                                             "fn_complexity_limits",
+                                             "the complexity limits of functions \
+                                              are experimental",
+                                             cfg_fn!(fn_complexity_limits))),
 ];
 
 // cfg(...)'s that are feature gated
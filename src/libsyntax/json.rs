@@ -112,6 +112,9 @@ struct DiagnosticSpan {
     /// load the fully rendered version from the parent `Diagnostic`,
     /// however.
     suggested_replacement: Option<String>,
+    /// Whether `suggested_replacement` is certain to be correct, so that
+    /// tools may apply it without asking.
+    machine_applicable: bool,
     /// Macro invocations that created the code at this span, if any.
     expansion: Option<Box<DiagnosticSpanMacroExpansion>>,
 }
@@ -253,6 +256,7 @@ impl DiagnosticSpan {
             is_primary: is_primary,
             text: DiagnosticSpanLine::from_span(span, je),
             suggested_replacement: suggestion.cloned(),
+            machine_applicable: false,
             expansion: backtrace_step,
             label: label,
         }
@@ -271,10 +275,12 @@ impl DiagnosticSpan {
         suggestion.msp.span_labels()
                       .into_iter()
                       .zip(&suggestion.substitutes)
-                      .map(|(span_label, suggestion)| {
-                          DiagnosticSpan::from_span_label(span_label,
-                                                          Some(suggestion),
-                                                          je)
+                      .map(|(span_label, substitute)| {
+                          let mut span = DiagnosticSpan::from_span_label(span_label,
+                                                                         Some(substitute),
+                                                                         je);
+                          span.machine_applicable = suggestion.machine_applicable;
+                          span
                       })
                       .collect()
     }
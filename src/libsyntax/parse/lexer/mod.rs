@@ -175,12 +175,26 @@ impl<'a> StringReader<'a> {
     }
 
     pub fn new(sess: &'a ParseSess, filemap: Rc<syntax_pos::FileMap>) -> Self {
+        match StringReader::try_new(sess, filemap) {
+            Ok(sr) => sr,
+            Err(errs) => {
+                for mut err in errs {
+                    err.emit();
+                }
+                panic!(FatalError);
+            }
+        }
+    }
+
+    /// Like `new`, but returns the errors of lexing the first token instead
+    /// of reporting them and aborting.
+    pub fn try_new(sess: &'a ParseSess, filemap: Rc<syntax_pos::FileMap>)
+                   -> Result<Self, Vec<DiagnosticBuilder<'a>>> {
         let mut sr = StringReader::new_raw(sess, filemap);
         if let Err(_) = sr.advance_token() {
-            sr.emit_fatal_errors();
-            panic!(FatalError);
+            return Err(replace(&mut sr.fatal_errs, Vec::new()));
         }
-        sr
+        Ok(sr)
     }
 
     pub fn ch_is(&self, c: char) -> bool {
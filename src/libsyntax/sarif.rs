@@ -0,0 +1,310 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A SARIF emitter for errors.
+//!
+//! SARIF is the OASIS standard format for the results of static analysis
+//! tools, see http://docs.oasis-open.org/sarif/sarif/v2.1.0/. A SARIF log is
+//! a single JSON document, so unlike the other emitters this one cannot write
+//! the diagnostics as they come. They are collected instead, and the log is
+//! written when the emitter is dropped at the end of compilation.
+//!
+//! Diagnostics reported before the session exists, such as warnings about
+//! command line options, are given to the session's emitter so that they end
+//! up in the same log.
+//!
+//! Each diagnostic becomes a result whose rule is its error code. Primary
+//! spans are its locations and secondary spans its related locations. Child
+//! diagnostics with spans are related locations too, those without spans are
+//! appended to the message, and suggestions become fixes.
+
+use codemap::CodeMap;
+use syntax_pos::{Span, MultiSpan};
+use errors::registry::Registry;
+use errors::{DiagnosticBuilder, Level, RenderSpan, CodeSuggestion, CodeMapper};
+use errors::emitter::Emitter;
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::mem;
+use std::rc::Rc;
+use std::io::{self, Write};
+
+use rustc_serialize::json::{Json, ToJson, as_pretty_json};
+
+const SARIF_SCHEMA: &'static str =
+    "https://schemastore.azurewebsites.net/schemas/json/sarif-2.1.0.json";
+
+pub struct SarifEmitter {
+    dst: Box<Write + Send>,
+    registry: Option<Registry>,
+    cm: Rc<CodeMapper + 'static>,
+    results: Vec<Json>,
+    /// The error codes of the results, which are the rules of the log.
+    codes: BTreeSet<String>,
+    /// Whether this emitter reports early diagnostics, which it leaves to the
+    /// next emitter instead of writing a log.
+    early: bool,
+}
+
+/// The results of early diagnostics not taken over by an emitter yet.
+#[derive(Default)]
+struct EarlyResults {
+    results: Vec<Json>,
+    codes: BTreeSet<String>,
+}
+
+thread_local! {
+    static EARLY_RESULTS: RefCell<EarlyResults> = RefCell::new(EarlyResults::default())
+}
+
+impl Drop for EarlyResults {
+    fn drop(&mut self) {
+        // Early diagnostics without any emitter after them, e.g. when only
+        // printing the version, still get their log when the thread exits.
+        if !self.results.is_empty() {
+            drop(SarifEmitter {
+                dst: Box::new(io::stderr()),
+                registry: None,
+                cm: Rc::new(CodeMap::new()),
+                results: mem::replace(&mut self.results, vec![]),
+                codes: mem::replace(&mut self.codes, BTreeSet::new()),
+                early: false,
+            });
+        }
+    }
+}
+
+impl SarifEmitter {
+    pub fn stderr(registry: Option<Registry>,
+                  code_map: Rc<CodeMap>) -> SarifEmitter {
+        SarifEmitter::new(Box::new(io::stderr()), registry, code_map)
+    }
+
+    pub fn basic() -> SarifEmitter {
+        SarifEmitter::stderr(None, Rc::new(CodeMap::new()))
+    }
+
+    /// An emitter for the diagnostics reported before the session exists.
+    /// Rather than writing a log of its own, it leaves its results to the
+    /// next emitter.
+    pub fn early() -> SarifEmitter {
+        SarifEmitter {
+            dst: Box::new(io::stderr()),
+            registry: None,
+            cm: Rc::new(CodeMap::new()),
+            results: vec![],
+            codes: BTreeSet::new(),
+            early: true,
+        }
+    }
+
+    pub fn new(dst: Box<Write + Send>,
+               registry: Option<Registry>,
+               code_map: Rc<CodeMap>) -> SarifEmitter {
+        let (results, codes) = EARLY_RESULTS.with(|early| {
+            let mut early = early.borrow_mut();
+            (mem::replace(&mut early.results, vec![]),
+             mem::replace(&mut early.codes, BTreeSet::new()))
+        });
+        SarifEmitter {
+            dst: dst,
+            registry: registry,
+            cm: code_map,
+            results: results,
+            codes: codes,
+            early: false,
+        }
+    }
+}
+
+impl Emitter for SarifEmitter {
+    fn emit(&mut self, db: &DiagnosticBuilder) {
+        let result = self.result(db);
+        self.results.push(result);
+    }
+}
+
+impl Drop for SarifEmitter {
+    fn drop(&mut self) {
+        if self.early {
+            let results = mem::replace(&mut self.results, vec![]);
+            let codes = mem::replace(&mut self.codes, BTreeSet::new());
+            EARLY_RESULTS.with(|early| {
+                let mut early = early.borrow_mut();
+                early.results.extend(results);
+                early.codes.extend(codes);
+            });
+            return;
+        }
+        let log = self.log();
+        // We may be unwinding from a fatal error here, so failing to write the
+        // log must not panic.
+        let _ = writeln!(&mut self.dst, "{}", as_pretty_json(&log));
+        let _ = self.dst.flush();
+    }
+}
+
+fn object(fields: Vec<(&str, Json)>) -> Json {
+    Json::Object(fields.into_iter().map(|(k, v)| (k.to_owned(), v)).collect())
+}
+
+fn message(text: &str) -> Json {
+    object(vec![("text", text.to_json())])
+}
+
+fn level(level: Level) -> &'static str {
+    match level {
+        Level::Bug | Level::Fatal | Level::PhaseFatal | Level::Error => "error",
+        Level::Warning => "warning",
+        Level::Note | Level::Help | Level::Cancelled => "note",
+    }
+}
+
+impl SarifEmitter {
+    fn log(&self) -> Json {
+        let rules = self.codes.iter().map(|code| {
+            let mut rule = vec![("id", code.to_json())];
+            let explanation = self.registry
+                                  .as_ref()
+                                  .and_then(|registry| registry.find_description(code));
+            if let Some(explanation) = explanation {
+                rule.push(("fullDescription", message(explanation)));
+            }
+            object(rule)
+        }).collect::<Vec<_>>();
+        let driver = object(vec![
+            ("name", "rustc".to_json()),
+            ("informationUri", "https://www.rust-lang.org/".to_json()),
+            ("rules", Json::Array(rules)),
+        ]);
+        let run = object(vec![
+            ("tool", object(vec![("driver", driver)])),
+            // The columns of the code map count characters.
+            ("columnKind", "unicodeCodePoints".to_json()),
+            ("results", Json::Array(self.results.clone())),
+        ]);
+        object(vec![
+            ("$schema", SARIF_SCHEMA.to_json()),
+            ("version", "2.1.0".to_json()),
+            ("runs", Json::Array(vec![run])),
+        ])
+    }
+
+    fn result(&mut self, db: &DiagnosticBuilder) -> Json {
+        let mut text = db.message();
+        let mut locations = vec![];
+        let mut related_locations = vec![];
+        let mut fixes = vec![];
+
+        for span_label in db.span.span_labels() {
+            let location = self.location(span_label.span, span_label.label);
+            if span_label.is_primary {
+                locations.push(location);
+            } else {
+                related_locations.push(location);
+            }
+        }
+
+        for child in &db.children {
+            let child_text = format!("{}: {}", child.level.to_str(), child.message());
+            match child.render_span {
+                Some(RenderSpan::Suggestion(ref suggestion)) => {
+                    fixes.push(self.fix(&child.message(), suggestion));
+                }
+                Some(RenderSpan::FullSpan(ref msp)) => {
+                    related_locations.extend(self.child_locations(msp, &child_text));
+                }
+                None if child.span.primary_spans().is_empty() => {
+                    text.push('\n');
+                    text.push_str(&child_text);
+                }
+                None => {
+                    related_locations.extend(self.child_locations(&child.span, &child_text));
+                }
+            }
+        }
+
+        let mut result = vec![
+            ("level", level(db.level).to_json()),
+            ("message", message(&text)),
+            ("locations", Json::Array(locations)),
+        ];
+        if let Some(ref code) = db.code {
+            self.codes.insert(code.clone());
+            result.push(("ruleId", code.to_json()));
+        }
+        if !related_locations.is_empty() {
+            result.push(("relatedLocations", Json::Array(related_locations)));
+        }
+        if !fixes.is_empty() {
+            result.push(("fixes", Json::Array(fixes)));
+        }
+        object(result)
+    }
+
+    fn child_locations(&self, msp: &MultiSpan, text: &str) -> Vec<Json> {
+        msp.primary_spans().iter().map(|&span| {
+            self.location(span, Some(text.to_owned()))
+        }).collect()
+    }
+
+    fn location(&self, span: Span, label: Option<String>) -> Json {
+        let file_name = self.cm.span_to_filename(span);
+        let mut location = vec![
+            ("physicalLocation", object(vec![
+                ("artifactLocation", object(vec![("uri", file_name.to_json())])),
+                ("region", self.region(span)),
+            ])),
+        ];
+        if let Some(label) = label {
+            location.push(("message", message(&label)));
+        }
+        object(location)
+    }
+
+    fn region(&self, span: Span) -> Json {
+        let start = self.cm.lookup_char_pos(span.lo);
+        let end = self.cm.lookup_char_pos(span.hi);
+        object(vec![
+            ("startLine", start.line.to_json()),
+            ("startColumn", (start.col.0 + 1).to_json()),
+            ("endLine", end.line.to_json()),
+            ("endColumn", (end.col.0 + 1).to_json()),
+        ])
+    }
+
+    fn fix(&self, description: &str, suggestion: &CodeSuggestion) -> Json {
+        let mut replacements = BTreeMap::new();
+        for (&span, substitute) in suggestion.msp.primary_spans().iter()
+                                             .zip(&suggestion.substitutes) {
+            let replacement = object(vec![
+                ("deletedRegion", self.region(span)),
+                ("insertedContent", message(substitute)),
+            ]);
+            replacements.entry(self.cm.span_to_filename(span))
+                        .or_insert_with(Vec::new)
+                        .push(replacement);
+        }
+        let changes = replacements.into_iter().map(|(file_name, replacements)| {
+            object(vec![
+                ("artifactLocation", object(vec![("uri", file_name.to_json())])),
+                ("replacements", Json::Array(replacements)),
+            ])
+        }).collect();
+        object(vec![
+            ("description", message(description)),
+            ("artifactChanges", Json::Array(changes)),
+            ("properties", object(vec![
+                ("machineApplicable", suggestion.machine_applicable.to_json()),
+            ])),
+        ])
+    }
+}
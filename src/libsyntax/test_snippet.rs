@@ -932,3 +932,58 @@ error: foo
 
 "#);
 }
+
+#[test]
+fn note_on_longer_line_number() {
+    let file_text = r#"
+fn foo() {
+}
+
+
+
+
+
+
+
+fn bar() {
+}
+"#;
+    let output = Arc::new(Mutex::new(Vec::new()));
+
+    let code_map = Rc::new(CodeMap::new());
+    code_map.new_filemap_and_lines("test.rs", None, file_text);
+
+    let foo = make_span(file_text,
+                        &Position { string: "foo", count: 1 },
+                        &Position { string: "foo", count: 1 });
+    let bar = make_span(file_text,
+                        &Position { string: "bar", count: 1 },
+                        &Position { string: "bar", count: 1 });
+
+    let emitter = EmitterWriter::new(Box::new(Shared { data: output.clone() }),
+                                     Some(code_map.clone()));
+    let handler = Handler::with_emitter(true, false, Box::new(emitter));
+    handler.struct_span_err(foo, "foo").span_note(bar, "bar").emit();
+
+    // The line number of the note decides the width of the gutter of both snippets.
+    let expected_output = r#"error: foo
+  --> test.rs:2:4
+   |
+2  | fn foo() {
+   |    ^^^
+   |
+note: bar
+  --> test.rs:11:4
+   |
+11 | fn bar() {
+   |    ^^^
+
+"#;
+
+    let bytes = output.lock().unwrap();
+    let actual_output = str::from_utf8(&bytes).unwrap();
+    println!("expected output:\n------\n{}------", expected_output);
+    println!("actual output:\n------\n{}------", actual_output);
+
+    assert!(expected_output == actual_output)
+}
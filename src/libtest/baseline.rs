@@ -0,0 +1,181 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Saved benchmark results, used by `--save-baseline` and `--baseline`.
+//!
+//! A baseline is a JSON file mapping benchmark names to their results,
+//! stored under `BASELINE_DIR` in the current directory.
+
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+
+use serialize::json::{self, as_pretty_json};
+
+use super::BenchSamples;
+
+/// The directory baselines are stored in, relative to the working directory.
+pub const BASELINE_DIR: &'static str = ".bench-baselines";
+
+#[derive(RustcEncodable, RustcDecodable, Clone, PartialEq, Debug)]
+pub struct BenchRecord {
+    /// Nanoseconds per iteration.
+    pub median: f64,
+    pub deviation: f64,
+    pub mb_s: usize,
+}
+
+impl BenchRecord {
+    pub fn new(bs: &BenchSamples) -> BenchRecord {
+        BenchRecord {
+            median: bs.ns_iter_summ.median,
+            deviation: bs.ns_iter_summ.max - bs.ns_iter_summ.min,
+            mb_s: bs.mb_s,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum BenchChange {
+    /// The benchmark is not in the baseline.
+    Added,
+    /// The median changed by the given percentage, but by no more than the
+    /// noise threshold.
+    LikelyNoise(f64),
+    /// The median went down by the given percentage.
+    Improvement(f64),
+    /// The median went up by the given percentage.
+    Regression(f64),
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Baseline(BTreeMap<String, BenchRecord>);
+
+impl Baseline {
+    pub fn new() -> Baseline {
+        Baseline(BTreeMap::new())
+    }
+
+    /// Whether `name` can be used as the name of a baseline: it must be the
+    /// name of a file directly in `BASELINE_DIR`.
+    pub fn is_valid_name(name: &str) -> bool {
+        !name.is_empty() && !name.contains(|c: char| c == '/' || c == '\\') && !name.contains("..")
+    }
+
+    pub fn path(name: &str) -> PathBuf {
+        assert!(Baseline::is_valid_name(name));
+        PathBuf::from(BASELINE_DIR).join(format!("{}.json", name))
+    }
+
+    pub fn insert(&mut self, name: &str, bs: &BenchSamples) {
+        self.0.insert(name.to_owned(), BenchRecord::new(bs));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Loads the baseline saved as `name`.
+    pub fn load(name: &str) -> io::Result<Baseline> {
+        let path = Baseline::path(name);
+        let mut contents = String::new();
+        File::open(&path).and_then(|mut f| f.read_to_string(&mut contents)).map_err(|e| {
+            io::Error::new(e.kind(),
+                           format!("failed to read baseline `{}` from {}: {}",
+                                   name, path.display(), e))
+        })?;
+        json::decode(&contents).map(Baseline).map_err(|e| {
+            io::Error::new(io::ErrorKind::InvalidData,
+                           format!("failed to parse baseline `{}` in {}: {}",
+                                   name, path.display(), e))
+        })
+    }
+
+    /// Saves this baseline as `name`, replacing any previous baseline of
+    /// that name.
+    pub fn save(&self, name: &str) -> io::Result<()> {
+        fs::create_dir_all(BASELINE_DIR)?;
+        let mut file = File::create(Baseline::path(name))?;
+        writeln!(file, "{}", as_pretty_json(&self.0))
+    }
+
+    /// Compares the benchmarks of this run against those of `old`. A change
+    /// of the median by more than `noise_pct` percent in either direction
+    /// counts as an improvement or a regression. Benchmarks that are only in
+    /// `old` are left out.
+    pub fn compare_to_old(&self, old: &Baseline, noise_pct: f64) -> Vec<(String, BenchChange)> {
+        self.0.iter().map(|(name, new)| {
+            let change = match old.0.get(name) {
+                None => BenchChange::Added,
+                Some(old) => {
+                    let pct = if old.median > 0.0 {
+                        (new.median - old.median) / old.median * 100.0
+                    } else {
+                        0.0
+                    };
+                    if pct > noise_pct {
+                        BenchChange::Regression(pct)
+                    } else if pct < -noise_pct {
+                        BenchChange::Improvement(pct)
+                    } else {
+                        BenchChange::LikelyNoise(pct)
+                    }
+                }
+            };
+            (name.clone(), change)
+        }).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Baseline, BenchRecord, BenchChange};
+
+    fn record(median: f64) -> BenchRecord {
+        BenchRecord {
+            median: median,
+            deviation: 1.0,
+            mb_s: 0,
+        }
+    }
+
+    #[test]
+    fn baseline_names() {
+        assert!(Baseline::is_valid_name("main"));
+        assert!(Baseline::is_valid_name("before-fix.2"));
+        assert!(!Baseline::is_valid_name(""));
+        assert!(!Baseline::is_valid_name("../../x"));
+        assert!(!Baseline::is_valid_name(".."));
+        assert!(!Baseline::is_valid_name("a/b"));
+        assert!(!Baseline::is_valid_name("a\\b"));
+    }
+
+    #[test]
+    fn compare_baselines() {
+        let mut old = Baseline::new();
+        old.0.insert("noise".to_owned(), record(1000.0));
+        old.0.insert("slower".to_owned(), record(1000.0));
+        old.0.insert("faster".to_owned(), record(1000.0));
+        old.0.insert("removed".to_owned(), record(1000.0));
+
+        let mut new = Baseline::new();
+        new.0.insert("noise".to_owned(), record(1020.0));
+        new.0.insert("slower".to_owned(), record(1500.0));
+        new.0.insert("faster".to_owned(), record(500.0));
+        new.0.insert("added".to_owned(), record(1000.0));
+
+        assert_eq!(new.compare_to_old(&old, 5.0),
+                   vec![("added".to_owned(), BenchChange::Added),
+                        ("faster".to_owned(), BenchChange::Improvement(-50.0)),
+                        ("noise".to_owned(), BenchChange::LikelyNoise(2.0)),
+                        ("slower".to_owned(), BenchChange::Regression(50.0))]);
+    }
+}
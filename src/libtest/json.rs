@@ -0,0 +1,306 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The JSON event stream used by `--format json`.
+//!
+//! Every event of a test run is written as a single JSON object on its own
+//! line. The objects are produced by serialising the structs below, so
+//! their fields specify the format: `kind` is one of `"suite"`, `"test"`,
+//! `"bench"` or `"metric"`, and `event` says what happened to it.
+//!
+//! The format should be considered *unstable* for now.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use serialize::{Encodable, Encoder};
+
+use baseline::BenchChange;
+
+use super::{TestDesc, TestResult, MetricMap, BenchSamples};
+use super::{TrOk, TrFailed, TrFailedMsg, TrIgnored, TrMetrics, TrBench};
+
+#[derive(RustcEncodable)]
+pub struct SuiteStarted {
+    kind: &'static str,
+    event: &'static str,
+    test_count: usize,
+    /// The seed the tests were shuffled with, if `--shuffle` was given.
+    shuffle_seed: Option<u64>,
+}
+
+impl SuiteStarted {
+    pub fn new(test_count: usize, shuffle_seed: Option<u64>) -> SuiteStarted {
+        SuiteStarted {
+            kind: "suite",
+            event: "started",
+            test_count: test_count,
+            shuffle_seed: shuffle_seed,
+        }
+    }
+}
+
+#[derive(RustcEncodable)]
+pub struct SuiteFinished {
+    kind: &'static str,
+    /// "ok" or "failed".
+    event: &'static str,
+    passed: usize,
+    failed: usize,
+    ignored: usize,
+    measured: usize,
+    /// The slowest tests, slowest first, if `--slowest` was given.
+    slowest: Option<Vec<SlowTest>>,
+}
+
+#[derive(RustcEncodable)]
+struct SlowTest {
+    name: String,
+    /// In seconds.
+    exec_time: f64,
+}
+
+impl SuiteFinished {
+    pub fn new(success: bool,
+               passed: usize,
+               failed: usize,
+               ignored: usize,
+               measured: usize,
+               slowest: Option<Vec<(Duration, String)>>)
+               -> SuiteFinished {
+        let slowest = slowest.map(|slowest| {
+            slowest.into_iter().map(|(time, name)| {
+                SlowTest { name: name, exec_time: secs(time) }
+            }).collect()
+        });
+        SuiteFinished {
+            kind: "suite",
+            event: if success { "ok" } else { "failed" },
+            passed: passed,
+            failed: failed,
+            ignored: ignored,
+            measured: measured,
+            slowest: slowest,
+        }
+    }
+}
+
+#[derive(RustcEncodable)]
+pub struct TestStarted {
+    kind: &'static str,
+    event: &'static str,
+    name: String,
+}
+
+impl TestStarted {
+    pub fn new(desc: &TestDesc) -> TestStarted {
+        TestStarted {
+            kind: "test",
+            event: "started",
+            name: desc.name.to_string(),
+        }
+    }
+}
+
+#[derive(RustcEncodable)]
+pub struct TestTimeout {
+    kind: &'static str,
+    event: &'static str,
+    name: String,
+}
+
+impl TestTimeout {
+    pub fn new(desc: &TestDesc) -> TestTimeout {
+        TestTimeout {
+            kind: "test",
+            event: "timeout",
+            name: desc.name.to_string(),
+        }
+    }
+}
+
+#[derive(RustcEncodable)]
+pub struct TestFinished {
+    kind: &'static str,
+    /// "ok", "failed" or "ignored".
+    event: &'static str,
+    name: String,
+    /// The message explaining the failure, if any.
+    message: Option<String>,
+    /// Captured stdout/stderr of the test, if it printed anything.
+    stdout: Option<String>,
+    /// How long the test ran for in seconds, if `--report-time` was given.
+    exec_time: Option<f64>,
+}
+
+#[derive(RustcEncodable)]
+pub struct BenchFinished {
+    kind: &'static str,
+    event: &'static str,
+    name: String,
+    /// Nanoseconds per iteration.
+    median: f64,
+    deviation: f64,
+    /// Bounds of the 95% confidence interval of the median, with
+    /// `--bench-stats`.
+    median_lower: Option<f64>,
+    median_upper: Option<f64>,
+    outliers: Option<usize>,
+    mb_s: Option<usize>,
+}
+
+#[derive(RustcEncodable)]
+pub struct MetricFinished {
+    kind: &'static str,
+    event: &'static str,
+    name: String,
+    metrics: BTreeMap<String, MetricValue>,
+}
+
+#[derive(RustcEncodable, PartialEq)]
+struct MetricValue {
+    value: f64,
+    noise: f64,
+}
+
+/// The event reported when a test, benchmark or metric has completed.
+pub enum ResultEvent {
+    Test(TestFinished),
+    Bench(BenchFinished),
+    Metric(MetricFinished),
+}
+
+impl ResultEvent {
+    pub fn new(desc: &TestDesc,
+               result: &TestResult,
+               stdout: &[u8],
+               exec_time: Option<Duration>)
+               -> ResultEvent {
+        let name = desc.name.to_string();
+        let exec_time = exec_time.map(secs);
+        let captured = if stdout.is_empty() {
+            None
+        } else {
+            Some(String::from_utf8_lossy(stdout).into_owned())
+        };
+        match *result {
+            TrOk => ResultEvent::Test(TestFinished {
+                kind: "test",
+                event: "ok",
+                name: name,
+                message: None,
+                stdout: captured,
+                exec_time: exec_time,
+            }),
+            TrFailed => ResultEvent::Test(TestFinished {
+                kind: "test",
+                event: "failed",
+                name: name,
+                message: None,
+                stdout: captured,
+                exec_time: exec_time,
+            }),
+            TrFailedMsg(ref msg) => ResultEvent::Test(TestFinished {
+                kind: "test",
+                event: "failed",
+                name: name,
+                message: Some(msg.clone()),
+                stdout: captured,
+                exec_time: exec_time,
+            }),
+            TrIgnored => ResultEvent::Test(TestFinished {
+                kind: "test",
+                event: "ignored",
+                name: name,
+                message: None,
+                stdout: None,
+                exec_time: None,
+            }),
+            TrBench(ref bs) => ResultEvent::Bench(BenchFinished::new(name, bs)),
+            TrMetrics(ref mm) => ResultEvent::Metric(MetricFinished::new(name, mm)),
+        }
+    }
+}
+
+impl Encodable for ResultEvent {
+    fn encode<S: Encoder>(&self, s: &mut S) -> Result<(), S::Error> {
+        match *self {
+            ResultEvent::Test(ref e) => e.encode(s),
+            ResultEvent::Bench(ref e) => e.encode(s),
+            ResultEvent::Metric(ref e) => e.encode(s),
+        }
+    }
+}
+
+impl BenchFinished {
+    fn new(name: String, bs: &BenchSamples) -> BenchFinished {
+        BenchFinished {
+            kind: "bench",
+            event: "ok",
+            name: name,
+            median: bs.ns_iter_summ.median,
+            deviation: bs.ns_iter_summ.max - bs.ns_iter_summ.min,
+            median_lower: bs.stats.map(|s| s.median_ci.lower),
+            median_upper: bs.stats.map(|s| s.median_ci.upper),
+            outliers: bs.stats.map(|s| s.outliers.total()),
+            mb_s: if bs.mb_s != 0 { Some(bs.mb_s) } else { None },
+        }
+    }
+}
+
+impl MetricFinished {
+    fn new(name: String, mm: &MetricMap) -> MetricFinished {
+        let MetricMap(ref map) = *mm;
+        let metrics = map.iter().map(|(k, m)| {
+            (k.clone(), MetricValue { value: m.value, noise: m.noise })
+        }).collect();
+        MetricFinished {
+            kind: "metric",
+            event: "ok",
+            name: name,
+            metrics: metrics,
+        }
+    }
+}
+
+#[derive(RustcEncodable)]
+pub struct BaselineComparison {
+    kind: &'static str,
+    event: &'static str,
+    name: String,
+    baseline: String,
+    /// "new", "noise", "improvement" or "regression".
+    change: &'static str,
+    /// Change of the median, in percent. `None` for new benchmarks.
+    change_pct: Option<f64>,
+}
+
+impl BaselineComparison {
+    pub fn new(name: &str, baseline: &str, change: BenchChange) -> BaselineComparison {
+        let (change, change_pct) = match change {
+            BenchChange::Added => ("new", None),
+            BenchChange::LikelyNoise(pct) => ("noise", Some(pct)),
+            BenchChange::Improvement(pct) => ("improvement", Some(pct)),
+            BenchChange::Regression(pct) => ("regression", Some(pct)),
+        };
+        BaselineComparison {
+            kind: "bench",
+            event: "compared",
+            name: name.to_owned(),
+            baseline: baseline.to_owned(),
+            change: change,
+            change_pct: change_pct,
+        }
+    }
+}
+
+fn secs(dur: Duration) -> f64 {
+    dur.as_secs() as f64 + dur.subsec_nanos() as f64 / 1_000_000_000.0
+}
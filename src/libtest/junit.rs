@@ -0,0 +1,191 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A JUnit XML report writer, used by `--junit-xml`.
+//!
+//! Results are collected as the tests finish and the whole
+//! `<testsuites>` document is written once the run is over, since the
+//! suite element needs the final counts.
+
+use std::io::{self, Write};
+use std::time::Duration;
+
+use super::{TestDesc, TestResult, fmt_bench_samples, fmt_secs};
+use super::{TrOk, TrFailed, TrFailedMsg, TrIgnored, TrMetrics, TrBench};
+
+struct TestCase {
+    name: String,
+    result: TestResult,
+    stdout: Vec<u8>,
+    exec_time: Duration,
+}
+
+pub struct JUnitReport<W> {
+    out: W,
+    suite_name: String,
+    cases: Vec<TestCase>,
+}
+
+impl<W: Write> JUnitReport<W> {
+    pub fn new(out: W, suite_name: String) -> JUnitReport<W> {
+        JUnitReport {
+            out: out,
+            suite_name: suite_name,
+            cases: Vec::new(),
+        }
+    }
+
+    pub fn add_result(&mut self,
+                      desc: &TestDesc,
+                      result: &TestResult,
+                      stdout: &[u8],
+                      exec_time: Duration) {
+        self.cases.push(TestCase {
+            name: desc.name.to_string(),
+            result: result.clone(),
+            stdout: stdout.to_vec(),
+            exec_time: exec_time,
+        });
+    }
+
+    pub fn write_report(&mut self) -> io::Result<()> {
+        let failures = self.cases.iter().filter(|c| match c.result {
+            TrFailed | TrFailedMsg(_) => true,
+            _ => false,
+        }).count();
+        let skipped = self.cases.iter().filter(|c| c.result == TrIgnored).count();
+        let total_time = self.cases.iter().fold(Duration::new(0, 0), |t, c| t + c.exec_time);
+
+        writeln!(self.out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(self.out, "<testsuites>")?;
+        writeln!(self.out,
+                 concat!(r#"<testsuite name="{}" tests="{}" failures="{}" errors="0" "#,
+                         r#"skipped="{}" time="{}">"#),
+                 escape(&self.suite_name),
+                 self.cases.len(),
+                 failures,
+                 skipped,
+                 fmt_secs(total_time))?;
+        for case in &self.cases {
+            // JUnit wants a class name, so the module path of the test is
+            // used as one, with the suite name for tests at the crate root.
+            let (class_name, name) = match case.name.rfind("::") {
+                Some(i) => (&case.name[..i], &case.name[i + 2..]),
+                None => (&self.suite_name[..], &case.name[..]),
+            };
+            write!(self.out,
+                   r#"<testcase classname="{}" name="{}" time="{}""#,
+                   escape(class_name),
+                   escape(name),
+                   fmt_secs(case.exec_time))?;
+
+            let stdout = String::from_utf8_lossy(&case.stdout);
+            match case.result {
+                TrOk if stdout.is_empty() => writeln!(self.out, "/>")?,
+                TrOk => {
+                    writeln!(self.out, ">")?;
+                    write_system_out(&mut self.out, &stdout)?;
+                    writeln!(self.out, "</testcase>")?;
+                }
+                TrIgnored => {
+                    writeln!(self.out, ">")?;
+                    writeln!(self.out, "<skipped/>")?;
+                    writeln!(self.out, "</testcase>")?;
+                }
+                TrFailed | TrFailedMsg(_) => {
+                    let message = match case.result {
+                        TrFailedMsg(ref msg) => &msg[..],
+                        _ => "test failed",
+                    };
+                    // The captured output is the most useful thing to show
+                    // for a failure, so it becomes the body of `<failure>`.
+                    writeln!(self.out, ">")?;
+                    writeln!(self.out,
+                             r#"<failure message="{}" type="panic">{}</failure>"#,
+                             escape(message),
+                             escape(&stdout))?;
+                    writeln!(self.out, "</testcase>")?;
+                }
+                TrBench(ref bs) => {
+                    writeln!(self.out, ">")?;
+                    write_system_out(&mut self.out, &fmt_bench_samples(bs))?;
+                    writeln!(self.out, "</testcase>")?;
+                }
+                TrMetrics(ref mm) => {
+                    writeln!(self.out, ">")?;
+                    write_system_out(&mut self.out, &mm.fmt_metrics())?;
+                    writeln!(self.out, "</testcase>")?;
+                }
+            }
+        }
+        writeln!(self.out, "</testsuite>")?;
+        writeln!(self.out, "</testsuites>")?;
+        self.out.flush()
+    }
+}
+
+fn write_system_out<W: Write>(out: &mut W, s: &str) -> io::Result<()> {
+    writeln!(out, "<system-out>{}</system-out>", escape(s))
+}
+
+/// Escapes `s` for use in XML text and attribute values. Control
+/// characters are not allowed in XML 1.0 at all, so they are dropped.
+fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if c.is_control() => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::JUnitReport;
+    use std::time::Duration;
+    use {TestDesc, StaticTestName, ShouldPanic, TrOk, TrFailedMsg, TrIgnored};
+
+    fn desc(name: &'static str) -> TestDesc {
+        TestDesc {
+            name: StaticTestName(name),
+            ignore: false,
+            should_panic: ShouldPanic::No,
+        }
+    }
+
+    #[test]
+    fn junit_report() {
+        let mut report = JUnitReport::new(Vec::new(), "suite".to_owned());
+        report.add_result(&desc("a::pass"), &TrOk, b"", Duration::from_millis(1500));
+        report.add_result(&desc("fail"),
+                          &TrFailedMsg("expected <foo>".to_owned()),
+                          b"out & about",
+                          Duration::from_millis(2));
+        report.add_result(&desc("a::b::skip"), &TrIgnored, b"", Duration::new(0, 0));
+        report.write_report().unwrap();
+
+        let xml = String::from_utf8(report.out).unwrap();
+        assert!(xml.contains(r#"tests="3" failures="1" errors="0" skipped="1" time="1.502""#));
+        assert!(xml.contains(r#"<testcase classname="a" name="pass" time="1.500"/>"#));
+        assert!(xml.contains(r#"<testcase classname="suite" name="fail" time="0.002">"#));
+        assert!(xml.contains(
+            r#"<failure message="expected &lt;foo&gt;" type="panic">out &amp; about</failure>"#));
+        assert!(xml.contains("<testcase classname=\"a::b\" name=\"skip\" time=\"0.000\">\n\
+                              <skipped/>"));
+    }
+}
@@ -39,8 +39,13 @@
 #![feature(set_stdio)]
 #![feature(staged_api)]
 #![feature(panic_unwind)]
+#![feature(process_try_wait)]
+#![feature(rand)]
 
 extern crate getopts;
+extern crate rand;
+extern crate serialize;
+extern crate serialize as rustc_serialize; // used by deriving
 extern crate term;
 extern crate libc;
 extern crate panic_unwind;
@@ -63,14 +68,25 @@ use std::fs::File;
 use std::io::prelude::*;
 use std::io;
 use std::iter::repeat;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus, Stdio};
 use std::sync::mpsc::{channel, Sender};
 use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::{Instant, Duration};
+use std::time::{Instant, Duration, SystemTime, UNIX_EPOCH};
+
+use rand::{Isaac64Rng, Rng, SeedableRng};
+
+use serialize::Encodable;
+use serialize::json::as_json;
+
+use baseline::{Baseline, BenchChange};
 
 const TEST_WARN_TIMEOUT_S: u64 = 60;
 
+// Set in the environment of the child processes spawned by `--isolate`.
+const ISOLATED_CHILD_ENV: &'static str = "__RUST_TEST_ISOLATED_CHILD";
+
 // to be used by rustc to compile tests in libtest
 pub mod test {
     pub use {Bencher, TestName, TestResult, TestDesc, TestDescAndFn, TestOpts, TrFailed,
@@ -80,6 +96,9 @@ pub mod test {
 }
 
 pub mod stats;
+mod baseline;
+mod json;
+mod junit;
 
 // The name of a test. By convention this follows the rules for rust
 // paths; i.e. it should be a series of identifiers separated by double
@@ -190,6 +209,8 @@ pub struct Bencher {
     mode: BenchMode,
     summary: Option<stats::Summary>,
     pub bytes: u64,
+    config: bench::BenchConfig,
+    stats: Option<BenchStats>,
 }
 
 #[derive(Clone, PartialEq, Eq)]
@@ -260,7 +281,11 @@ pub fn test_main(args: &[String], tests: Vec<TestDescAndFn>) {
         Some(Err(msg)) => panic!("{:?}", msg),
         None => return,
     };
-    if opts.list {
+    if env::var_os(ISOLATED_CHILD_ENV).is_some() {
+        if !run_tests_isolated_child(&opts, tests) {
+            std::process::exit(101);
+        }
+    } else if opts.list {
         if let Err(e) = list_tests_console(&opts, tests) {
             panic!("io error when listing tests: {:?}", e);
         }
@@ -311,6 +336,14 @@ pub enum ColorConfig {
     NeverColor,
 }
 
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Human-readable output, one line (or character) per test.
+    Pretty,
+    /// One JSON object per line for every event of the run.
+    Json,
+}
+
 pub struct TestOpts {
     pub list: bool,
     pub filter: Option<String>,
@@ -319,11 +352,23 @@ pub struct TestOpts {
     pub run_tests: bool,
     pub bench_benchmarks: bool,
     pub logfile: Option<PathBuf>,
+    pub junit_xml: Option<PathBuf>,
     pub nocapture: bool,
     pub color: ColorConfig,
+    pub format: OutputFormat,
     pub quiet: bool,
+    pub report_time: bool,
+    pub slowest: Option<usize>,
     pub test_threads: Option<usize>,
     pub skip: Vec<String>,
+    pub isolate: bool,
+    pub test_timeout: Option<Duration>,
+    pub shuffle_seed: Option<u64>,
+    pub shard: Option<(usize, usize)>, // (index, count)
+    pub bench_config: bench::BenchConfig,
+    pub save_baseline: Option<String>,
+    pub baseline: Option<String>,
+    pub noise_threshold: f64,
 }
 
 impl TestOpts {
@@ -337,11 +382,23 @@ impl TestOpts {
             run_tests: false,
             bench_benchmarks: false,
             logfile: None,
+            junit_xml: None,
             nocapture: false,
             color: AutoColor,
+            format: OutputFormat::Pretty,
             quiet: false,
+            report_time: false,
+            slowest: None,
             test_threads: None,
             skip: vec![],
+            isolate: false,
+            test_timeout: None,
+            shuffle_seed: None,
+            shard: None,
+            bench_config: bench::BenchConfig::default(),
+            save_baseline: None,
+            baseline: None,
+            noise_threshold: 5.0,
         }
     }
 }
@@ -355,21 +412,51 @@ fn optgroups() -> Vec<getopts::OptGroup> {
       getopts::optflag("", "test", "Run tests and not benchmarks"),
       getopts::optflag("", "bench", "Run benchmarks instead of tests"),
       getopts::optflag("", "list", "List all tests and benchmarks"),
+      getopts::optopt("", "save-baseline", "Save the benchmark results as a baseline \
+                                            called NAME", "NAME"),
+      getopts::optopt("", "baseline", "Compare the benchmark results to the saved \
+                                       baseline called NAME, and fail on regressions", "NAME"),
+      getopts::optopt("", "noise-threshold", "Percentage by which a benchmark may change \
+                                              before --baseline reports it (default 5)", "PCT"),
+      getopts::optopt("", "warm-up-time", "Run each benchmark for MS milliseconds before \
+                                           measuring it (default 100)", "MS"),
+      getopts::optopt("", "measurement-time", "Spend at most MS milliseconds measuring each \
+                                               benchmark (default 3000)", "MS"),
+      getopts::optflag("", "bench-stats", "Report a 95% confidence interval of the median \
+                                           and the outliers of each benchmark"),
       getopts::optflag("h", "help", "Display this message (longer with --help)"),
       getopts::optopt("", "logfile", "Write logs to the specified file instead \
                           of stdout", "PATH"),
+      getopts::optopt("", "junit-xml", "Write a JUnit XML report of the test run \
+                                        to the specified file", "PATH"),
       getopts::optflag("", "nocapture", "don't capture stdout/stderr of each \
                                          task, allow printing directly"),
       getopts::optopt("", "test-threads", "Number of threads used for running tests \
                                            in parallel", "n_threads"),
+      getopts::optflag("", "isolate", "Run each test in its own child process of the test \
+                                       binary, so that a crash only fails that test"),
+      getopts::optopt("", "test-timeout", "Kill and fail tests that run for longer than \
+                                           SECS seconds (implies --isolate)", "SECS"),
       getopts::optmulti("", "skip", "Skip tests whose names contain FILTER (this flag can \
                                      be used multiple times)","FILTER"),
+      getopts::optflag("", "shuffle", "Run tests in a random order"),
+      getopts::optopt("", "shuffle-seed", "Run tests in the random order given by SEED \
+                                           (implies --shuffle)", "SEED"),
+      getopts::optopt("", "shard-index", "Only run the I-th (starting from 0) of the \
+                                          --shard-count slices of the tests", "I"),
+      getopts::optopt("", "shard-count", "Split the tests into N disjoint slices, to be \
+                                          run by different invocations", "N"),
       getopts::optflag("q", "quiet", "Display one character per test instead of one line"),
       getopts::optflag("", "exact", "Exactly match filters rather than by substring"),
+      getopts::optflag("", "report-time", "Show the execution time of each test"),
+      getopts::optopt("", "slowest", "List the N slowest tests at the end of the run", "N"),
       getopts::optopt("", "color", "Configure coloring of output:
             auto   = colorize if stdout is a tty and tests are run on serially (default);
             always = always colorize output;
-            never  = never colorize output;", "auto|always|never")]
+            never  = never colorize output;", "auto|always|never"),
+      getopts::optopt("", "format", "Configure formatting of output:
+            pretty = print verbose output (default);
+            json   = print one JSON object per test event;", "pretty|json")]
 }
 
 fn usage(binary: &str) {
@@ -431,6 +518,8 @@ pub fn parse_opts(args: &[String]) -> Option<OptRes> {
     let logfile = matches.opt_str("logfile");
     let logfile = logfile.map(|s| PathBuf::from(&s));
 
+    let junit_xml = matches.opt_str("junit-xml").map(|s| PathBuf::from(&s));
+
     let bench_benchmarks = matches.opt_present("bench");
     let run_tests = !bench_benchmarks || matches.opt_present("test");
 
@@ -454,6 +543,105 @@ pub fn parse_opts(args: &[String]) -> Option<OptRes> {
             None,
     };
 
+    let test_timeout = match matches.opt_str("test-timeout") {
+        Some(secs_str) =>
+            match secs_str.parse::<u64>() {
+                Ok(secs) if secs > 0 => Some(Duration::from_secs(secs)),
+                _ =>
+                    return Some(Err(format!("argument for --test-timeout must be a number of \
+                                             seconds > 0 (was {})", secs_str)))
+            },
+        None =>
+            None,
+    };
+    let isolate = matches.opt_present("isolate") || test_timeout.is_some();
+
+    let shuffle_seed = match matches.opt_str("shuffle-seed") {
+        Some(seed_str) =>
+            match seed_str.parse::<u64>() {
+                Ok(seed) => Some(seed),
+                Err(e) =>
+                    return Some(Err(format!("argument for --shuffle-seed must be a number \
+                                             (error: {})", e)))
+            },
+        None if matches.opt_present("shuffle") => Some(random_shuffle_seed()),
+        None => None,
+    };
+
+    let shard = match (matches.opt_str("shard-index"), matches.opt_str("shard-count")) {
+        (Some(index_str), Some(count_str)) => {
+            match (index_str.parse::<usize>(), count_str.parse::<usize>()) {
+                (Ok(index), Ok(count)) if index < count => Some((index, count)),
+                _ => {
+                    return Some(Err(format!("arguments for --shard-index and --shard-count \
+                                             must be numbers with 0 <= index < count \
+                                             (were {} and {})",
+                                            index_str, count_str)))
+                }
+            }
+        }
+        (None, None) => None,
+        _ => {
+            return Some(Err("--shard-index and --shard-count must be given together".to_owned()))
+        }
+    };
+
+    let save_baseline = matches.opt_str("save-baseline");
+    let baseline = matches.opt_str("baseline");
+    if (save_baseline.is_some() || baseline.is_some()) && !bench_benchmarks {
+        return Some(Err("--save-baseline and --baseline can only be used with --bench"
+                            .to_owned()));
+    }
+    for name in save_baseline.iter().chain(baseline.iter()) {
+        if !Baseline::is_valid_name(name) {
+            return Some(Err(format!("invalid baseline name `{}`: names must not be empty or \
+                                     contain `/`, `\\` or `..`", name)));
+        }
+    }
+
+    let mut bench_config = bench::BenchConfig::default();
+    if let Some(ms_str) = matches.opt_str("warm-up-time") {
+        match ms_str.parse::<u64>() {
+            Ok(ms) => bench_config.warm_up_time = Duration::from_millis(ms),
+            Err(e) =>
+                return Some(Err(format!("argument for --warm-up-time must be a number of \
+                                         milliseconds (error: {})", e)))
+        }
+    }
+    if let Some(ms_str) = matches.opt_str("measurement-time") {
+        match ms_str.parse::<u64>() {
+            Ok(ms) if ms > 0 => bench_config.measurement_time = Duration::from_millis(ms),
+            _ =>
+                return Some(Err(format!("argument for --measurement-time must be a number of \
+                                         milliseconds > 0 (was {})", ms_str)))
+        }
+    }
+    bench_config.report_stats = matches.opt_present("bench-stats");
+
+    let noise_threshold = match matches.opt_str("noise-threshold") {
+        Some(pct_str) =>
+            match pct_str.parse::<f64>() {
+                Ok(pct) if pct >= 0.0 => pct,
+                _ =>
+                    return Some(Err(format!("argument for --noise-threshold must be a \
+                                             non-negative percentage (was {})", pct_str)))
+            },
+        None =>
+            5.0,
+    };
+
+    let slowest = match matches.opt_str("slowest") {
+        Some(n_str) =>
+            match n_str.parse::<usize>() {
+                Ok(n) => Some(n),
+                Err(e) =>
+                    return Some(Err(format!("argument for --slowest must be a number \
+                                             (error: {})", e)))
+            },
+        None =>
+            None,
+    };
+
     let color = match matches.opt_str("color").as_ref().map(|s| &**s) {
         Some("auto") | None => AutoColor,
         Some("always") => AlwaysColor,
@@ -466,6 +654,17 @@ pub fn parse_opts(args: &[String]) -> Option<OptRes> {
         }
     };
 
+    let format = match matches.opt_str("format").as_ref().map(|s| &**s) {
+        Some("pretty") | None => OutputFormat::Pretty,
+        Some("json") => OutputFormat::Json,
+
+        Some(v) => {
+            return Some(Err(format!("argument for --format must be pretty or json (was \
+                                     {})",
+                                    v)))
+        }
+    };
+
     let test_opts = TestOpts {
         list: list,
         filter: filter,
@@ -474,20 +673,48 @@ pub fn parse_opts(args: &[String]) -> Option<OptRes> {
         run_tests: run_tests,
         bench_benchmarks: bench_benchmarks,
         logfile: logfile,
+        junit_xml: junit_xml,
         nocapture: nocapture,
         color: color,
+        format: format,
         quiet: quiet,
+        report_time: matches.opt_present("report-time"),
+        slowest: slowest,
         test_threads: test_threads,
         skip: matches.opt_strs("skip"),
+        isolate: isolate,
+        test_timeout: test_timeout,
+        shuffle_seed: shuffle_seed,
+        shard: shard,
+        bench_config: bench_config,
+        save_baseline: save_baseline,
+        baseline: baseline,
+        noise_threshold: noise_threshold,
     };
 
     Some(Ok(test_opts))
 }
 
+// Picks a seed for `--shuffle`. It only has to differ from run to run, and
+// is printed so that the order can be reproduced with `--shuffle-seed`.
+fn random_shuffle_seed() -> u64 {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or(Duration::new(0, 0));
+    now.as_secs().wrapping_mul(1_000_000_000).wrapping_add(now.subsec_nanos() as u64)
+}
+
 #[derive(Clone, PartialEq)]
 pub struct BenchSamples {
     ns_iter_summ: stats::Summary,
     mb_s: usize,
+    stats: Option<BenchStats>,
+}
+
+/// Statistics about a benchmark's samples that are only computed with
+/// `--bench-stats`.
+#[derive(Clone, Copy, PartialEq)]
+struct BenchStats {
+    median_ci: stats::ConfidenceInterval,
+    outliers: stats::Outliers,
 }
 
 #[derive(Clone, PartialEq)]
@@ -509,9 +736,14 @@ enum OutputLocation<T> {
 
 struct ConsoleTestState<T> {
     log_out: Option<File>,
+    junit: Option<junit::JUnitReport<File>>,
     out: OutputLocation<T>,
     use_color: bool,
+    format: OutputFormat,
     quiet: bool,
+    report_time: bool,
+    slowest: Option<usize>,
+    shuffle_seed: Option<u64>,
     total: usize,
     passed: usize,
     failed: usize,
@@ -519,6 +751,9 @@ struct ConsoleTestState<T> {
     measured: usize,
     metrics: MetricMap,
     failures: Vec<(TestDesc, Vec<u8>)>,
+    exec_times: Vec<(TestDesc, Duration)>,
+    bench_results: Baseline,
+    regressions: usize,
     max_name_len: usize, // number of columns to fill when aligning names
 }
 
@@ -528,6 +763,17 @@ impl<T: Write> ConsoleTestState<T> {
             Some(ref path) => Some(File::create(path)?),
             None => None,
         };
+        let junit = match opts.junit_xml {
+            Some(ref path) => {
+                let suite_name = env::args().next()
+                                            .as_ref()
+                                            .and_then(|arg0| Path::new(arg0).file_stem())
+                                            .map(|stem| stem.to_string_lossy().into_owned())
+                                            .unwrap_or_else(|| "test".to_owned());
+                Some(junit::JUnitReport::new(File::create(path)?, suite_name))
+            }
+            None => None,
+        };
         let out = match term::stdout() {
             None => Raw(io::stdout()),
             Some(t) => Pretty(t),
@@ -536,8 +782,13 @@ impl<T: Write> ConsoleTestState<T> {
         Ok(ConsoleTestState {
             out: out,
             log_out: log_out,
+            junit: junit,
             use_color: use_color(opts),
+            format: opts.format,
             quiet: opts.quiet,
+            report_time: opts.report_time,
+            slowest: opts.slowest,
+            shuffle_seed: opts.shuffle_seed,
             total: 0,
             passed: 0,
             failed: 0,
@@ -545,20 +796,23 @@ impl<T: Write> ConsoleTestState<T> {
             measured: 0,
             metrics: MetricMap::new(),
             failures: Vec::new(),
+            exec_times: Vec::new(),
+            bench_results: Baseline::new(),
+            regressions: 0,
             max_name_len: 0,
         })
     }
 
-    pub fn write_ok(&mut self) -> io::Result<()> {
-        self.write_short_result("ok", ".", term::color::GREEN)
+    pub fn write_ok(&mut self, exec_time: Duration) -> io::Result<()> {
+        self.write_short_result("ok", ".", term::color::GREEN, Some(exec_time))
     }
 
-    pub fn write_failed(&mut self) -> io::Result<()> {
-        self.write_short_result("FAILED", "F", term::color::RED)
+    pub fn write_failed(&mut self, exec_time: Duration) -> io::Result<()> {
+        self.write_short_result("FAILED", "F", term::color::RED, Some(exec_time))
     }
 
     pub fn write_ignored(&mut self) -> io::Result<()> {
-        self.write_short_result("ignored", "i", term::color::YELLOW)
+        self.write_short_result("ignored", "i", term::color::YELLOW, None)
     }
 
     pub fn write_metric(&mut self) -> io::Result<()> {
@@ -569,12 +823,19 @@ impl<T: Write> ConsoleTestState<T> {
         self.write_pretty("bench", term::color::CYAN)
     }
 
-    pub fn write_short_result(&mut self, verbose: &str, quiet: &str, color: term::color::Color)
+    pub fn write_short_result(&mut self,
+                              verbose: &str,
+                              quiet: &str,
+                              color: term::color::Color,
+                              exec_time: Option<Duration>)
                               -> io::Result<()> {
         if self.quiet {
             self.write_pretty(quiet, color)
         } else {
             self.write_pretty(verbose, color)?;
+            if let (true, Some(exec_time)) = (self.report_time, exec_time) {
+                self.write_plain(&format!(" <{}s>", fmt_secs(exec_time)))?;
+            }
             self.write_plain("\n")
         }
     }
@@ -612,18 +873,33 @@ impl<T: Write> ConsoleTestState<T> {
         }
     }
 
+    pub fn write_json<E: Encodable>(&mut self, event: &E) -> io::Result<()> {
+        self.write_plain(&format!("{}\n", as_json(event)))
+    }
+
     pub fn write_run_start(&mut self, len: usize) -> io::Result<()> {
         self.total = len;
+        if self.format == OutputFormat::Json {
+            return self.write_json(&json::SuiteStarted::new(len, self.shuffle_seed));
+        }
         let noun = if len != 1 {
             "tests"
         } else {
             "test"
         };
-        self.write_plain(&format!("\nrunning {} {}\n", len, noun))
+        self.write_plain(&format!("\nrunning {} {}\n", len, noun))?;
+        if let Some(seed) = self.shuffle_seed {
+            self.write_plain(&format!("shuffling tests with seed {} \
+                                       (pass --shuffle-seed {} to reproduce this order)\n",
+                                      seed, seed))?;
+        }
+        Ok(())
     }
 
     pub fn write_test_start(&mut self, test: &TestDesc, align: NamePadding) -> io::Result<()> {
-        if self.quiet && align != PadOnRight {
+        if self.format == OutputFormat::Json {
+            self.write_json(&json::TestStarted::new(test))
+        } else if self.quiet && align != PadOnRight {
             Ok(())
         } else {
             let name = test.padded_name(self.max_name_len, align);
@@ -631,10 +907,10 @@ impl<T: Write> ConsoleTestState<T> {
         }
     }
 
-    pub fn write_result(&mut self, result: &TestResult) -> io::Result<()> {
+    pub fn write_result(&mut self, result: &TestResult, exec_time: Duration) -> io::Result<()> {
         match *result {
-            TrOk => self.write_ok(),
-            TrFailed | TrFailedMsg(_) => self.write_failed(),
+            TrOk => self.write_ok(exec_time),
+            TrFailed | TrFailedMsg(_) => self.write_failed(exec_time),
             TrIgnored => self.write_ignored(),
             TrMetrics(ref mm) => {
                 self.write_metric()?;
@@ -647,7 +923,20 @@ impl<T: Write> ConsoleTestState<T> {
         }
     }
 
+    pub fn write_json_result(&mut self,
+                             test: &TestDesc,
+                             result: &TestResult,
+                             stdout: &[u8],
+                             exec_time: Duration)
+                             -> io::Result<()> {
+        let exec_time = if self.report_time { Some(exec_time) } else { None };
+        self.write_json(&json::ResultEvent::new(test, result, stdout, exec_time))
+    }
+
     pub fn write_timeout(&mut self, desc: &TestDesc) -> io::Result<()> {
+        if self.format == OutputFormat::Json {
+            return self.write_json(&json::TestTimeout::new(desc));
+        }
         self.write_plain(&format!("test {} has been running for over {} seconds\n",
                                   desc.name,
                                   TEST_WARN_TIMEOUT_S))
@@ -701,11 +990,99 @@ impl<T: Write> ConsoleTestState<T> {
         Ok(())
     }
 
+    // The `n` slowest tests, slowest first.
+    fn slowest_tests(&self, n: usize) -> Vec<(Duration, String)> {
+        let mut slowest = self.exec_times.iter()
+                                         .map(|&(ref desc, time)| (time, desc.name.to_string()))
+                                         .collect::<Vec<_>>();
+        slowest.sort_by(|a, b| b.cmp(a));
+        slowest.truncate(n);
+        slowest
+    }
+
+    pub fn write_slowest(&mut self, n: usize) -> io::Result<()> {
+        let slowest = self.slowest_tests(n);
+        if slowest.is_empty() {
+            return Ok(());
+        }
+
+        self.write_plain(&format!("\nslowest {} tests:\n", slowest.len()))?;
+        for (time, name) in slowest {
+            let time = format!("{}s", fmt_secs(time));
+            self.write_plain(&format!("    {:>10} {}\n", time, name))?;
+        }
+        Ok(())
+    }
+
+    /// Prints how the benchmarks of this run compare to the baseline `name`.
+    /// Regressions make the whole run fail.
+    pub fn write_baseline_comparison(&mut self, name: &str, old: &Baseline, noise_pct: f64)
+                                     -> io::Result<()> {
+        let changes = self.bench_results.compare_to_old(old, noise_pct);
+        self.regressions = changes.iter().filter(|&&(_, change)| match change {
+            BenchChange::Regression(_) => true,
+            _ => false,
+        }).count();
+
+        if self.format == OutputFormat::Json {
+            for &(ref bench, change) in &changes {
+                self.write_json(&json::BaselineComparison::new(bench, name, change))?;
+            }
+            return Ok(());
+        }
+
+        self.write_plain(&format!("\ncomparison with baseline `{}` (noise threshold {}%):\n",
+                                  name, noise_pct))?;
+        let width = changes.iter().map(|&(ref bench, _)| bench.len()).max().unwrap_or(0);
+        for (bench, change) in changes {
+            self.write_plain(&format!("    {:<width$} ", bench, width = width))?;
+            match change {
+                BenchChange::Added => self.write_plain("new")?,
+                BenchChange::LikelyNoise(pct) => {
+                    self.write_plain(&format!("{:+.2}% (within noise)", pct))?
+                }
+                BenchChange::Improvement(pct) => {
+                    self.write_pretty(&format!("{:+.2}%", pct), term::color::GREEN)?;
+                    self.write_plain(" (improvement)")?;
+                }
+                BenchChange::Regression(pct) => {
+                    self.write_pretty(&format!("{:+.2}%", pct), term::color::RED)?;
+                    self.write_plain(" (regression)")?;
+                }
+            }
+            self.write_plain("\n")?;
+        }
+        if self.regressions > 0 {
+            let s = format!("{} benchmark(s) regressed\n", self.regressions);
+            self.write_plain(&s)?;
+        }
+        Ok(())
+    }
+
     pub fn write_run_finish(&mut self) -> io::Result<bool> {
         assert!(self.passed + self.failed + self.ignored + self.measured == self.total);
 
-        let success = self.failed == 0;
-        if !success {
+        if let Some(ref mut junit) = self.junit {
+            junit.write_report()?;
+        }
+
+        let success = self.failed == 0 && self.regressions == 0;
+        if self.format == OutputFormat::Json {
+            let slowest = self.slowest.map(|n| self.slowest_tests(n));
+            self.write_json(&json::SuiteFinished::new(success,
+                                                      self.passed,
+                                                      self.failed,
+                                                      self.ignored,
+                                                      self.measured,
+                                                      slowest))?;
+            return Ok(success);
+        }
+
+        if let Some(n) = self.slowest {
+            self.write_slowest(n)?;
+        }
+
+        if self.failed != 0 {
             self.write_failures()?;
         }
 
@@ -716,11 +1093,15 @@ impl<T: Write> ConsoleTestState<T> {
         } else {
             self.write_pretty("FAILED", term::color::RED)?;
         }
-        let s = format!(". {} passed; {} failed; {} ignored; {} measured\n\n",
-                        self.passed,
-                        self.failed,
-                        self.ignored,
-                        self.measured);
+        let mut s = format!(". {} passed; {} failed; {} ignored; {} measured",
+                            self.passed,
+                            self.failed,
+                            self.ignored,
+                            self.measured);
+        if self.regressions > 0 {
+            s.push_str(&format!("; {} regressed", self.regressions));
+        }
+        s.push_str("\n\n");
         self.write_plain(&s)?;
         return Ok(success);
     }
@@ -750,6 +1131,11 @@ fn fmt_thousands_sep(mut n: usize, sep: char) -> String {
     output
 }
 
+// Format a duration as seconds with millisecond precision, e.g. `1.234`
+fn fmt_secs(dur: Duration) -> String {
+    format!("{}.{:03}", dur.as_secs(), dur.subsec_nanos() / 1_000_000)
+}
+
 pub fn fmt_bench_samples(bs: &BenchSamples) -> String {
     use std::fmt::Write;
     let mut output = String::new();
@@ -764,6 +1150,22 @@ pub fn fmt_bench_samples(bs: &BenchSamples) -> String {
     if bs.mb_s != 0 {
         output.write_fmt(format_args!(" = {} MB/s", bs.mb_s)).unwrap();
     }
+    if let Some(ref bench_stats) = bs.stats {
+        let ci = &bench_stats.median_ci;
+        let outliers = &bench_stats.outliers;
+        output.write_fmt(format_args!(", {}% CI [{}, {}]",
+                                      ci.level,
+                                      fmt_thousands_sep(ci.lower as usize, ','),
+                                      fmt_thousands_sep(ci.upper as usize, ',')))
+              .unwrap();
+        if outliers.total() != 0 {
+            output.write_fmt(format_args!(", {} outlier(s) ({} mild, {} severe)",
+                                          outliers.total(),
+                                          outliers.mild(),
+                                          outliers.severe()))
+                  .unwrap();
+        }
+    }
     output
 }
 
@@ -810,6 +1212,28 @@ pub fn list_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::Res
     Ok(())
 }
 
+// Runs the tests in a child process spawned by `--isolate`. The parent
+// captures our output and looks at the exit status, so nothing but the
+// tests themselves print anything here.
+fn run_tests_isolated_child(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> bool {
+    let tests = convert_benchmarks_to_tests(filter_tests(opts, tests));
+
+    let (tx, rx) = channel::<MonitorMsg>();
+    let mut success = true;
+    for test in tests {
+        run_test(opts, false, test, tx.clone());
+        match rx.recv().unwrap().1 {
+            TrFailed => success = false,
+            TrFailedMsg(msg) => {
+                let _ = writeln!(io::stderr(), "note: {}", msg);
+                success = false;
+            }
+            _ => {}
+        }
+    }
+    success
+}
+
 // A simple console test runner
 pub fn run_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::Result<bool> {
 
@@ -818,9 +1242,19 @@ pub fn run_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::Resu
             TeFiltered(ref filtered_tests) => st.write_run_start(filtered_tests.len()),
             TeWait(ref test, padding) => st.write_test_start(test, padding),
             TeTimeout(ref test) => st.write_timeout(test),
-            TeResult(test, result, stdout) => {
+            TeResult(test, result, stdout, exec_time) => {
                 st.write_log_result(&test, &result)?;
-                st.write_result(&result)?;
+                if let Some(ref mut junit) = st.junit {
+                    junit.add_result(&test, &result, &stdout, exec_time);
+                }
+                if st.format == OutputFormat::Json {
+                    st.write_json_result(&test, &result, &stdout, exec_time)?;
+                } else {
+                    st.write_result(&result, exec_time)?;
+                }
+                if result != TrIgnored {
+                    st.exec_times.push((test.clone(), exec_time));
+                }
                 match result {
                     TrOk => st.passed += 1,
                     TrIgnored => st.ignored += 1,
@@ -834,6 +1268,7 @@ pub fn run_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::Resu
                         st.measured += 1
                     }
                     TrBench(bs) => {
+                        st.bench_results.insert(test.name.as_slice(), &bs);
                         st.metrics.insert_metric(test.name.as_slice(),
                                                  bs.ns_iter_summ.median,
                                                  bs.ns_iter_summ.max - bs.ns_iter_summ.min);
@@ -868,10 +1303,57 @@ pub fn run_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::Resu
         let n = t.desc.name.as_slice();
         st.max_name_len = n.len();
     }
+    // Load the baseline up front so that a missing one is reported before
+    // spending time on the benchmarks.
+    let old_baseline = match opts.baseline {
+        Some(ref name) => Some(Baseline::load(name)?),
+        None => None,
+    };
+
     run_tests(opts, tests, |x| callback(&x, &mut st))?;
+
+    if let (Some(name), Some(old)) = (opts.baseline.as_ref(), old_baseline.as_ref()) {
+        st.write_baseline_comparison(name, old, opts.noise_threshold)?;
+    }
+    // Don't replace a saved baseline with an empty one when the filters
+    // matched no benchmarks.
+    if let Some(ref name) = opts.save_baseline {
+        if !st.bench_results.is_empty() {
+            st.bench_results.save(name)?;
+        }
+    }
     return st.write_run_finish();
 }
 
+#[cfg(test)]
+impl ConsoleTestState<Vec<u8>> {
+    // A state writing uncolored output to a buffer, for testing what is written.
+    fn new_for_test(format: OutputFormat) -> ConsoleTestState<Vec<u8>> {
+        ConsoleTestState {
+            log_out: None,
+            junit: None,
+            out: Raw(Vec::new()),
+            use_color: false,
+            format: format,
+            quiet: false,
+            report_time: false,
+            slowest: None,
+            shuffle_seed: None,
+            total: 0,
+            passed: 0,
+            failed: 0,
+            ignored: 0,
+            measured: 0,
+            max_name_len: 10,
+            metrics: MetricMap::new(),
+            failures: Vec::new(),
+            exec_times: Vec::new(),
+            bench_results: Baseline::new(),
+            regressions: 0,
+        }
+    }
+}
+
 #[test]
 fn should_sort_failures_before_printing_them() {
     let test_a = TestDesc {
@@ -886,20 +1368,8 @@ fn should_sort_failures_before_printing_them() {
         should_panic: ShouldPanic::No,
     };
 
-    let mut st = ConsoleTestState {
-        log_out: None,
-        out: Raw(Vec::new()),
-        use_color: false,
-        quiet: false,
-        total: 0,
-        passed: 0,
-        failed: 0,
-        ignored: 0,
-        measured: 0,
-        max_name_len: 10,
-        metrics: MetricMap::new(),
-        failures: vec![(test_b, Vec::new()), (test_a, Vec::new())],
-    };
+    let mut st = ConsoleTestState::new_for_test(OutputFormat::Pretty);
+    st.failures = vec![(test_b, Vec::new()), (test_a, Vec::new())];
 
     st.write_failures().unwrap();
     let s = match st.out {
@@ -912,6 +1382,87 @@ fn should_sort_failures_before_printing_them() {
     assert!(apos < bpos);
 }
 
+#[test]
+fn should_list_slowest_tests_first() {
+    fn desc(name: &'static str) -> TestDesc {
+        TestDesc {
+            name: StaticTestName(name),
+            ignore: false,
+            should_panic: ShouldPanic::No,
+        }
+    }
+
+    let mut st = ConsoleTestState::new_for_test(OutputFormat::Pretty);
+    st.report_time = true;
+    st.slowest = Some(2);
+    st.exec_times = vec![(desc("fast"), Duration::from_millis(5)),
+                         (desc("slowest"), Duration::from_millis(2500)),
+                         (desc("slow"), Duration::from_millis(120))];
+
+    st.write_slowest(2).unwrap();
+    let s = match st.out {
+        Raw(ref m) => String::from_utf8_lossy(&m[..]).into_owned(),
+        Pretty(_) => unreachable!(),
+    };
+
+    assert_eq!(s, "\nslowest 2 tests:\n        2.500s slowest\n        0.120s slow\n");
+}
+
+#[test]
+fn should_write_json_events() {
+    let test = TestDesc {
+        name: StaticTestName("a::b"),
+        ignore: false,
+        should_panic: ShouldPanic::No,
+    };
+
+    let mut st = ConsoleTestState::new_for_test(OutputFormat::Json);
+    st.report_time = true;
+    st.slowest = Some(1);
+
+    st.write_run_start(1).unwrap();
+    st.write_test_start(&test, PadNone).unwrap();
+    let exec_time = Duration::from_millis(1500);
+    st.write_json_result(&test, &TrFailedMsg("boom".to_owned()), b"output", exec_time).unwrap();
+    st.failed += 1;
+    st.exec_times.push((test.clone(), exec_time));
+    st.write_run_finish().unwrap();
+    let s = match st.out {
+        Raw(ref m) => String::from_utf8_lossy(&m[..]).into_owned(),
+        Pretty(_) => unreachable!(),
+    };
+
+    let lines = s.lines().collect::<Vec<_>>();
+    assert_eq!(lines,
+               vec![r#"{"kind":"suite","event":"started","test_count":1,"shuffle_seed":null}"#,
+                    r#"{"kind":"test","event":"started","name":"a::b"}"#,
+                    concat!(r#"{"kind":"test","event":"failed","name":"a::b","#,
+                            r#""message":"boom","stdout":"output","exec_time":1.5}"#),
+                    concat!(r#"{"kind":"suite","event":"failed","passed":0,"failed":1,"#,
+                            r#""ignored":0,"measured":0,"#,
+                            r#""slowest":[{"name":"a::b","exec_time":1.5}]}"#)]);
+}
+
+#[test]
+fn should_write_json_stdout_of_passing_tests() {
+    let test = TestDesc {
+        name: StaticTestName("a::b"),
+        ignore: false,
+        should_panic: ShouldPanic::No,
+    };
+
+    let mut st = ConsoleTestState::new_for_test(OutputFormat::Json);
+    st.write_json_result(&test, &TrOk, b"output", Duration::from_millis(10)).unwrap();
+    let s = match st.out {
+        Raw(ref m) => String::from_utf8_lossy(&m[..]).into_owned(),
+        Pretty(_) => unreachable!(),
+    };
+
+    assert_eq!(s.trim_right(),
+               concat!(r#"{"kind":"test","event":"ok","name":"a::b","message":null,"#,
+                       r#""stdout":"output","exec_time":null}"#));
+}
+
 fn use_color(opts: &TestOpts) -> bool {
     match opts.color {
         AutoColor => !opts.nocapture && stdout_isatty(),
@@ -951,11 +1502,12 @@ fn stdout_isatty() -> bool {
 enum TestEvent {
     TeFiltered(Vec<TestDesc>),
     TeWait(TestDesc, NamePadding),
-    TeResult(TestDesc, TestResult, Vec<u8>),
+    TeResult(TestDesc, TestResult, Vec<u8>, Duration),
     TeTimeout(TestDesc),
 }
 
-pub type MonitorMsg = (TestDesc, TestResult, Vec<u8>);
+// The result of a test, its captured output and how long it ran for.
+pub type MonitorMsg = (TestDesc, TestResult, Vec<u8>, Duration);
 
 
 fn run_tests<F>(opts: &TestOpts, tests: Vec<TestDescAndFn>, mut callback: F) -> io::Result<()>
@@ -1048,13 +1600,13 @@ fn run_tests<F>(opts: &TestOpts, tests: Vec<TestDescAndFn>, mut callback: F) ->
             }
         }
 
-        let (desc, result, stdout) = res.unwrap();
+        let (desc, result, stdout, exec_time) = res.unwrap();
         running_tests.remove(&desc);
 
         if concurrency != 1 {
             callback(TeWait(desc.clone(), PadNone))?;
         }
-        callback(TeResult(desc, result, stdout))?;
+        callback(TeResult(desc, result, stdout, exec_time))?;
         pending -= 1;
     }
 
@@ -1064,8 +1616,8 @@ fn run_tests<F>(opts: &TestOpts, tests: Vec<TestDescAndFn>, mut callback: F) ->
         for b in filtered_benchs_and_metrics {
             callback(TeWait(b.desc.clone(), b.testfn.padding()))?;
             run_test(opts, false, b, tx.clone());
-            let (test, result, stdout) = rx.recv().unwrap();
-            callback(TeResult(test, result, stdout))?;
+            let (test, result, stdout, exec_time) = rx.recv().unwrap();
+            callback(TeResult(test, result, stdout, exec_time))?;
         }
     }
     Ok(())
@@ -1241,6 +1793,23 @@ pub fn filter_tests(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> Vec<TestDescA
     // Sort the tests alphabetically
     filtered.sort_by(|t1, t2| t1.desc.name.as_slice().cmp(t2.desc.name.as_slice()));
 
+    // Only keep our shard of them. This is done on the sorted list so that
+    // every invocation agrees on which shard a test belongs to.
+    if let Some((index, count)) = opts.shard {
+        filtered = filtered.into_iter()
+                           .enumerate()
+                           .filter(|&(i, _)| i % count == index)
+                           .map(|(_, test)| test)
+                           .collect();
+    }
+
+    // Shuffle them with the given seed. This starts from the sorted order so
+    // that the same seed always gives the same order.
+    if let Some(seed) = opts.shuffle_seed {
+        let mut rng: Isaac64Rng = SeedableRng::from_seed(&[seed][..]);
+        rng.shuffle(&mut filtered);
+    }
+
     filtered
 }
 
@@ -1275,7 +1844,7 @@ pub fn run_test(opts: &TestOpts,
     let TestDescAndFn {desc, testfn} = test;
 
     if force_ignore || desc.ignore {
-        monitor_ch.send((desc, TrIgnored, Vec::new())).unwrap();
+        monitor_ch.send((desc, TrIgnored, Vec::new(), Duration::new(0, 0))).unwrap();
         return;
     }
 
@@ -1308,9 +1877,11 @@ pub fn run_test(opts: &TestOpts,
                 None
             };
 
+            let start = Instant::now();
             let result = catch_unwind(AssertUnwindSafe(|| {
                 testfn.call_box(())
             }));
+            let exec_time = start.elapsed();
 
             if let Some((printio, panicio)) = oldio {
                 io::set_print(printio);
@@ -1319,7 +1890,7 @@ pub fn run_test(opts: &TestOpts,
 
             let test_result = calc_result(&desc, result);
             let stdout = data.lock().unwrap().to_vec();
-            monitor_ch.send((desc.clone(), test_result, stdout)).unwrap();
+            monitor_ch.send((desc.clone(), test_result, stdout, exec_time)).unwrap();
         };
 
 
@@ -1338,27 +1909,64 @@ pub fn run_test(opts: &TestOpts,
         }
     }
 
+    fn run_test_isolated(desc: TestDesc,
+                         monitor_ch: Sender<MonitorMsg>,
+                         opts: &TestOpts) {
+        let nocapture = opts.nocapture;
+        let run_ignored = opts.run_ignored;
+        let timeout = opts.test_timeout;
+
+        let name = desc.name.clone();
+        let runtest = move || {
+            let start = Instant::now();
+            let (test_result, stdout) =
+                match run_test_process(&desc, nocapture, run_ignored, timeout) {
+                    Ok(res) => res,
+                    Err(e) => {
+                        (TrFailedMsg(format!("failed to run the test in a child process: {}", e)),
+                         Vec::new())
+                    }
+                };
+            monitor_ch.send((desc.clone(), test_result, stdout, start.elapsed())).unwrap();
+        };
+
+        let cfg = thread::Builder::new().name(match name {
+            DynTestName(ref name) => name.clone(),
+            StaticTestName(name) => name.to_owned(),
+        });
+        cfg.spawn(runtest).unwrap();
+    }
+
     match testfn {
+        DynTestFn(_) | StaticTestFn(_) if opts.isolate => {
+            run_test_isolated(desc, monitor_ch, opts)
+        }
         DynBenchFn(bencher) => {
-            let bs = ::bench::benchmark(|harness| bencher.run(harness));
-            monitor_ch.send((desc, TrBench(bs), Vec::new())).unwrap();
+            let start = Instant::now();
+            let bs = ::bench::benchmark_with_config(opts.bench_config,
+                                                    |harness| bencher.run(harness));
+            monitor_ch.send((desc, TrBench(bs), Vec::new(), start.elapsed())).unwrap();
             return;
         }
         StaticBenchFn(benchfn) => {
-            let bs = ::bench::benchmark(|harness| (benchfn.clone())(harness));
-            monitor_ch.send((desc, TrBench(bs), Vec::new())).unwrap();
+            let start = Instant::now();
+            let bs = ::bench::benchmark_with_config(opts.bench_config,
+                                                    |harness| (benchfn.clone())(harness));
+            monitor_ch.send((desc, TrBench(bs), Vec::new(), start.elapsed())).unwrap();
             return;
         }
         DynMetricFn(f) => {
+            let start = Instant::now();
             let mut mm = MetricMap::new();
             f.call_box(&mut mm);
-            monitor_ch.send((desc, TrMetrics(mm), Vec::new())).unwrap();
+            monitor_ch.send((desc, TrMetrics(mm), Vec::new(), start.elapsed())).unwrap();
             return;
         }
         StaticMetricFn(f) => {
+            let start = Instant::now();
             let mut mm = MetricMap::new();
             f(&mut mm);
-            monitor_ch.send((desc, TrMetrics(mm), Vec::new())).unwrap();
+            monitor_ch.send((desc, TrMetrics(mm), Vec::new(), start.elapsed())).unwrap();
             return;
         }
         DynTestFn(f) => run_test_inner(desc, monitor_ch, opts.nocapture, f),
@@ -1367,6 +1975,95 @@ pub fn run_test(opts: &TestOpts,
     }
 }
 
+// Runs a single test by spawning the current test binary again, filtered
+// down to exactly that test, and returns its result and captured output.
+//
+// The child runs the test through `run_tests_isolated_child`, so its exit
+// status tells us the outcome: 0 means success and 101 a failed test, while
+// anything else (e.g. death by a signal) means the process crashed. If
+// `timeout` elapses first the child is killed and the test is reported as
+// failed.
+fn run_test_process(desc: &TestDesc,
+                    nocapture: bool,
+                    run_ignored: bool,
+                    timeout: Option<Duration>)
+                    -> io::Result<(TestResult, Vec<u8>)> {
+    // Reads `r` into `buf` on another thread until all writers close it.
+    fn read_all<R: Read + Send + 'static>(r: Option<R>, buf: Arc<Mutex<Vec<u8>>>)
+                                          -> thread::JoinHandle<()> {
+        thread::spawn(move || {
+            if let Some(mut r) = r {
+                let mut chunk = [0; 4096];
+                loop {
+                    match r.read(&mut chunk) {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => buf.lock().unwrap().extend_from_slice(&chunk[..n]),
+                    }
+                }
+            }
+        })
+    }
+
+    let mut cmd = Command::new(env::current_exe()?);
+    cmd.arg(desc.name.as_slice())
+       .arg("--exact")
+       .arg("--nocapture")
+       .env(ISOLATED_CHILD_ENV, "1");
+    if run_ignored {
+        cmd.arg("--ignored");
+    }
+    if !nocapture {
+        cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+    }
+
+    let mut child = cmd.spawn()?;
+    let stdout = Arc::new(Mutex::new(Vec::new()));
+    let stderr = Arc::new(Mutex::new(Vec::new()));
+    let stdout_reader = read_all(child.stdout.take(), stdout.clone());
+    let stderr_reader = read_all(child.stderr.take(), stderr.clone());
+
+    let deadline = timeout.map(|t| Instant::now() + t);
+    let status: Option<ExitStatus>;
+    loop {
+        match child.try_wait() {
+            Ok(s) => {
+                status = Some(s);
+                break;
+            }
+            Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => {}
+            Err(e) => return Err(e),
+        }
+        if deadline.map_or(false, |deadline| Instant::now() >= deadline) {
+            child.kill()?;
+            child.wait()?;
+            status = None;
+            break;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    // Processes started by a killed test may still hold the pipes open, so
+    // only wait for the readers to reach the end of the output if the test
+    // exited by itself, and otherwise take what has been read so far.
+    if status.is_some() {
+        let _ = stdout_reader.join();
+        let _ = stderr_reader.join();
+    }
+    let mut output = stdout.lock().unwrap().clone();
+    output.extend_from_slice(&stderr.lock().unwrap());
+
+    let result = match status {
+        Some(status) if status.success() => TrOk,
+        Some(status) if status.code() == Some(101) => TrFailed,
+        Some(status) => TrFailedMsg(format!("test process terminated abnormally ({})", status)),
+        None => {
+            TrFailedMsg(format!("test did not finish within {} seconds and was killed",
+                                timeout.unwrap().as_secs()))
+        }
+    };
+    Ok((result, output))
+}
+
 fn calc_result(desc: &TestDesc, task_result: Result<(), Box<Any + Send>>) -> TestResult {
     match (&desc.should_panic, task_result) {
         (&ShouldPanic::No, Ok(())) |
@@ -1455,7 +2152,9 @@ impl Bencher {
             return;
         }
 
-        self.summary = Some(iter(&mut inner));
+        let (summ, stats) = iter_with_config(&mut inner, &self.config);
+        self.summary = Some(summ);
+        self.stats = stats;
     }
 
     pub fn bench<F>(&mut self, mut f: F) -> Option<stats::Summary>
@@ -1484,8 +2183,27 @@ fn ns_iter_inner<T, F>(inner: &mut F, k: u64) -> u64
 pub fn iter<T, F>(inner: &mut F) -> stats::Summary
     where F: FnMut() -> T
 {
-    // Initial bench run to get ballpark figure.
-    let ns_single = ns_iter_inner(inner, 1);
+    iter_with_config(inner, &bench::BenchConfig::default()).0
+}
+
+fn iter_with_config<T, F>(inner: &mut F, config: &bench::BenchConfig)
+                          -> (stats::Summary, Option<BenchStats>)
+    where F: FnMut() -> T
+{
+    // Warm up caches, branch predictors and the like, running the benchmark
+    // at least once to get a ballpark figure of the time per iteration.
+    let warm_up_start = Instant::now();
+    let mut warm_up_iters = 0;
+    let mut k = 1;
+    loop {
+        ns_iter_inner(inner, k);
+        warm_up_iters += k;
+        if warm_up_start.elapsed() >= config.warm_up_time {
+            break;
+        }
+        k = k.saturating_mul(2);
+    }
+    let ns_single = ns_from_dur(warm_up_start.elapsed()) / warm_up_iters;
 
     // Try to estimate iter count for 1ms falling back to 1m
     // iterations if first run took < 1ns.
@@ -1516,22 +2234,22 @@ pub fn iter<T, F>(inner: &mut F) -> stats::Summary
             *p = ns as f64 / (5 * n) as f64;
         }
 
+        // Outliers and the confidence interval are of interest for the
+        // samples as they were measured, so they are computed before
+        // winsorizing.
+        let raw_samples = if config.report_stats { Some(samples.to_vec()) } else { None };
         stats::winsorize(samples, 5.0);
         let summ5 = stats::Summary::new(samples);
 
         let loop_run = loop_start.elapsed();
-
-        // If we've run for 100ms and seem to have converged to a
-        // stable median.
-        if loop_run > Duration::from_millis(100) && summ.median_abs_dev_pct < 1.0 &&
-           summ.median - summ5.median < summ5.median_abs_dev {
-            return summ5;
-        }
-
         total_run = total_run + loop_run;
-        // Longest we ever run for is 3s.
-        if total_run > Duration::from_secs(3) {
-            return summ5;
+
+        // Stop if we've run for 100ms and seem to have converged to a stable
+        // median, or if we're out of measurement time.
+        if (loop_run > Duration::from_millis(100) && summ.median_abs_dev_pct < 1.0 &&
+            summ.median - summ5.median < summ5.median_abs_dev) ||
+           total_run > config.measurement_time {
+            return (summ5, raw_samples.map(|s| bench_stats(&s)));
         }
 
         // If we overflow here just return the results so far. We check a
@@ -1541,24 +2259,65 @@ pub fn iter<T, F>(inner: &mut F) -> stats::Summary
         n = match n.checked_mul(10) {
             Some(_) => n * 2,
             None => {
-                return summ5;
+                return (summ5, raw_samples.map(|s| bench_stats(&s)));
             }
         };
     }
 }
 
+fn bench_stats(raw_samples: &[f64]) -> BenchStats {
+    // A fixed seed, so that the same samples always give the same interval.
+    BenchStats {
+        median_ci: stats::bootstrap_median(raw_samples, 95.0, 10_000, 0),
+        outliers: stats::Outliers::new(raw_samples),
+    }
+}
+
 pub mod bench {
     use std::cmp;
+    use std::time::Duration;
     use stats;
     use super::{Bencher, BenchSamples, BenchMode};
 
+    /// How `Bencher::iter` measures a benchmark.
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    pub struct BenchConfig {
+        /// How long the benchmark is run before any samples are taken.
+        pub warm_up_time: Duration,
+        /// How long samples are taken for at most, if the results have not
+        /// converged before.
+        pub measurement_time: Duration,
+        /// Whether to compute a confidence interval of the median and
+        /// classify outliers. Bootstrapping the interval takes a while, so
+        /// this is off by default.
+        pub report_stats: bool,
+    }
+
+    impl Default for BenchConfig {
+        fn default() -> BenchConfig {
+            BenchConfig {
+                warm_up_time: Duration::from_millis(100),
+                measurement_time: Duration::from_secs(3),
+                report_stats: false,
+            }
+        }
+    }
+
     pub fn benchmark<F>(f: F) -> BenchSamples
         where F: FnMut(&mut Bencher)
+    {
+        benchmark_with_config(BenchConfig::default(), f)
+    }
+
+    pub fn benchmark_with_config<F>(config: BenchConfig, f: F) -> BenchSamples
+        where F: FnMut(&mut Bencher)
     {
         let mut bs = Bencher {
             mode: BenchMode::Auto,
             summary: None,
             bytes: 0,
+            config: config,
+            stats: None,
         };
 
         return match bs.bench(f) {
@@ -1569,6 +2328,7 @@ pub mod bench {
                 BenchSamples {
                     ns_iter_summ: ns_iter_summ,
                     mb_s: mb_s as usize,
+                    stats: bs.stats,
                 }
             }
             None => {
@@ -1578,6 +2338,7 @@ pub mod bench {
                 BenchSamples {
                     ns_iter_summ: stats::Summary::new(samples),
                     mb_s: 0,
+                    stats: None,
                 }
             }
         };
@@ -1590,6 +2351,8 @@ pub mod bench {
             mode: BenchMode::Single,
             summary: None,
             bytes: 0,
+            config: BenchConfig::default(),
+            stats: None,
         };
         bs.bench(f);
     }
@@ -1599,10 +2362,16 @@ pub mod bench {
 mod tests {
     use test::{TrFailed, TrFailedMsg, TrIgnored, TrOk, filter_tests, parse_opts, TestDesc,
                TestDescAndFn, TestOpts, run_test, MetricMap, StaticTestName, DynTestName,
-               DynTestFn, ShouldPanic};
+               DynTestFn, ShouldPanic, TestResult};
+    use std::process;
     use std::sync::mpsc::channel;
+    use std::thread;
+    use std::time::Duration;
+    use OutputFormat;
     use bench;
+    use run_test_process;
     use Bencher;
+    use {BenchSamples, BenchStats, fmt_bench_samples, stats};
 
     #[test]
     pub fn do_not_run_ignored_tests() {
@@ -1619,7 +2388,7 @@ mod tests {
         };
         let (tx, rx) = channel();
         run_test(&TestOpts::new(), false, desc, tx);
-        let (_, res, _) = rx.recv().unwrap();
+        let (_, res, _, _) = rx.recv().unwrap();
         assert!(res != TrOk);
     }
 
@@ -1636,7 +2405,7 @@ mod tests {
         };
         let (tx, rx) = channel();
         run_test(&TestOpts::new(), false, desc, tx);
-        let (_, res, _) = rx.recv().unwrap();
+        let (_, res, _, _) = rx.recv().unwrap();
         assert!(res == TrIgnored);
     }
 
@@ -1655,7 +2424,7 @@ mod tests {
         };
         let (tx, rx) = channel();
         run_test(&TestOpts::new(), false, desc, tx);
-        let (_, res, _) = rx.recv().unwrap();
+        let (_, res, _, _) = rx.recv().unwrap();
         assert!(res == TrOk);
     }
 
@@ -1674,7 +2443,7 @@ mod tests {
         };
         let (tx, rx) = channel();
         run_test(&TestOpts::new(), false, desc, tx);
-        let (_, res, _) = rx.recv().unwrap();
+        let (_, res, _, _) = rx.recv().unwrap();
         assert!(res == TrOk);
     }
 
@@ -1695,7 +2464,7 @@ mod tests {
         };
         let (tx, rx) = channel();
         run_test(&TestOpts::new(), false, desc, tx);
-        let (_, res, _) = rx.recv().unwrap();
+        let (_, res, _, _) = rx.recv().unwrap();
         assert!(res == TrFailedMsg(format!("{} '{}'", failed_msg, expected)));
     }
 
@@ -1712,7 +2481,7 @@ mod tests {
         };
         let (tx, rx) = channel();
         run_test(&TestOpts::new(), false, desc, tx);
-        let (_, res, _) = rx.recv().unwrap();
+        let (_, res, _, _) = rx.recv().unwrap();
         assert!(res == TrFailed);
     }
 
@@ -1726,6 +2495,182 @@ mod tests {
         assert!((opts.run_ignored));
     }
 
+    #[test]
+    fn parse_format_flag() {
+        let args = vec!["progname".to_string(), "--format".to_string(), "json".to_string()];
+        let opts = match parse_opts(&args) {
+            Some(Ok(o)) => o,
+            _ => panic!("Malformed arg in parse_format_flag"),
+        };
+        assert!(opts.format == OutputFormat::Json);
+
+        let args = vec!["progname".to_string(), "--format".to_string(), "xml".to_string()];
+        assert!(parse_opts(&args).unwrap().is_err());
+    }
+
+    #[test]
+    fn parse_test_timeout_implies_isolate() {
+        let args = vec!["progname".to_string(), "--test-timeout".to_string(), "5".to_string()];
+        let opts = match parse_opts(&args) {
+            Some(Ok(o)) => o,
+            _ => panic!("Malformed arg in parse_test_timeout_implies_isolate"),
+        };
+        assert!(opts.isolate);
+        assert_eq!(opts.test_timeout, Some(Duration::from_secs(5)));
+
+        let args = vec!["progname".to_string(), "--test-timeout".to_string(), "0".to_string()];
+        assert!(parse_opts(&args).unwrap().is_err());
+    }
+
+    // The `isolated_child_*` tests are only run in child processes by the
+    // tests of `--isolate` below, which pass `--ignored` to them.
+
+    #[test]
+    #[ignore]
+    fn isolated_child_passes() {}
+
+    #[test]
+    #[ignore]
+    fn isolated_child_fails() {
+        panic!("failed in the child");
+    }
+
+    #[test]
+    #[ignore]
+    fn isolated_child_aborts() {
+        process::abort();
+    }
+
+    #[test]
+    #[ignore]
+    fn isolated_child_hangs() {
+        thread::sleep(Duration::from_secs(60));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    #[ignore]
+    fn isolated_child_leaves_grandchild() {
+        // The grandchild inherits the pipes of our output.
+        process::Command::new("sleep").arg("10").spawn().unwrap();
+        thread::sleep(Duration::from_secs(60));
+    }
+
+    fn run_isolated(name: &'static str, timeout: Option<Duration>) -> (TestResult, String) {
+        let desc = TestDesc {
+            name: StaticTestName(name),
+            ignore: true,
+            should_panic: ShouldPanic::No,
+        };
+        let (result, output) = run_test_process(&desc, false, true, timeout).unwrap();
+        (result, String::from_utf8_lossy(&output).into_owned())
+    }
+
+    fn failure_message(result: TestResult) -> String {
+        match result {
+            TrFailedMsg(msg) => msg,
+            _ => panic!("expected a failure with a message"),
+        }
+    }
+
+    #[test]
+    fn isolated_test_results() {
+        assert!(run_isolated("tests::isolated_child_passes", None).0 == TrOk);
+
+        let (result, output) = run_isolated("tests::isolated_child_fails", None);
+        assert!(result == TrFailed);
+        assert!(output.contains("failed in the child"));
+
+        let (result, _) = run_isolated("tests::isolated_child_aborts", None);
+        assert!(failure_message(result).contains("terminated abnormally"));
+    }
+
+    #[test]
+    fn isolated_test_timeout() {
+        let timeout = Some(Duration::from_secs(1));
+        let (result, _) = run_isolated("tests::isolated_child_hangs", timeout);
+        assert!(failure_message(result).contains("was killed"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn isolated_test_timeout_with_grandchild() {
+        // The grandchild keeps the pipes open for 10 seconds after the test
+        // is killed, which must not hold up the result.
+        let start = ::std::time::Instant::now();
+        let timeout = Some(Duration::from_secs(1));
+        let (result, _) = run_isolated("tests::isolated_child_leaves_grandchild", timeout);
+        assert!(failure_message(result).contains("was killed"));
+        assert!(start.elapsed() < Duration::from_secs(8));
+    }
+
+    #[test]
+    fn parse_bench_times() {
+        let args = vec!["progname".to_string(), "--bench".to_string()];
+        let opts = parse_opts(&args).unwrap().unwrap();
+        assert_eq!(opts.bench_config, bench::BenchConfig::default());
+
+        let args = vec!["progname".to_string(),
+                        "--bench".to_string(),
+                        "--warm-up-time".to_string(),
+                        "0".to_string(),
+                        "--measurement-time".to_string(),
+                        "500".to_string()];
+        let opts = parse_opts(&args).unwrap().unwrap();
+        assert_eq!(opts.bench_config.warm_up_time, Duration::from_millis(0));
+        assert_eq!(opts.bench_config.measurement_time, Duration::from_millis(500));
+        assert!(!opts.bench_config.report_stats);
+
+        let args = vec!["progname".to_string(), "--bench".to_string(), "--bench-stats".to_string()];
+        let opts = parse_opts(&args).unwrap().unwrap();
+        assert!(opts.bench_config.report_stats);
+
+        let args = vec!["progname".to_string(),
+                        "--measurement-time".to_string(),
+                        "0".to_string()];
+        assert!(parse_opts(&args).unwrap().is_err());
+    }
+
+    #[test]
+    fn bench_samples_format() {
+        let samples: &mut [f64] = &mut [1000.0, 1200.0, 1100.0];
+        let mut bs = BenchSamples {
+            ns_iter_summ: stats::Summary::new(samples),
+            mb_s: 0,
+            stats: None,
+        };
+        assert_eq!(fmt_bench_samples(&bs), "      1,100 ns/iter (+/- 200)");
+
+        bs.stats = Some(BenchStats {
+            median_ci: stats::ConfidenceInterval {
+                lower: 1000.0,
+                upper: 1200.0,
+                level: 95.0,
+            },
+            outliers: stats::Outliers::new(samples),
+        });
+        assert_eq!(fmt_bench_samples(&bs),
+                   "      1,100 ns/iter (+/- 200), 95% CI [1,000, 1,200]");
+    }
+
+    #[test]
+    fn parse_baseline_names() {
+        let args = vec!["progname".to_string(),
+                        "--bench".to_string(),
+                        "--save-baseline".to_string(),
+                        "main".to_string()];
+        let opts = parse_opts(&args).unwrap().unwrap();
+        assert_eq!(opts.save_baseline, Some("main".to_string()));
+
+        for name in &["../../x", "a/b", ""] {
+            let args = vec!["progname".to_string(),
+                            "--bench".to_string(),
+                            "--baseline".to_string(),
+                            name.to_string()];
+            assert!(parse_opts(&args).unwrap().is_err());
+        }
+    }
+
     #[test]
     pub fn filter_for_ignored_option() {
         // When we run ignored tests the test filter should filter out all the
@@ -1876,6 +2821,77 @@ mod tests {
         }
     }
 
+    // `n` passing tests called `test00`, `test01`, etc.
+    fn numbered_tests(n: usize) -> Vec<TestDescAndFn> {
+        (0..n).map(|i| TestDescAndFn {
+            desc: TestDesc {
+                name: DynTestName(format!("test{:02}", i)),
+                ignore: false,
+                should_panic: ShouldPanic::No,
+            },
+            testfn: DynTestFn(Box::new(move |()| {})),
+        }).collect()
+    }
+
+    #[test]
+    pub fn shards_are_disjoint() {
+        let mut all = Vec::new();
+        for index in 0..3 {
+            let opts = TestOpts { shard: Some((index, 3)), ..TestOpts::new() };
+            let shard = filter_tests(&opts, numbered_tests(10));
+            assert!(shard.len() == 3 || shard.len() == 4);
+            all.extend(shard.into_iter().map(|t| t.desc.name.to_string()));
+        }
+        all.sort();
+        let expected = filter_tests(&TestOpts::new(), numbered_tests(10))
+                           .into_iter()
+                           .map(|t| t.desc.name.to_string())
+                           .collect::<Vec<_>>();
+        assert_eq!(all, expected);
+    }
+
+    #[test]
+    fn parse_shard_flags() {
+        let args = vec!["progname".to_string(),
+                        "--shard-index".to_string(), "1".to_string(),
+                        "--shard-count".to_string(), "4".to_string()];
+        let opts = match parse_opts(&args) {
+            Some(Ok(o)) => o,
+            _ => panic!("Malformed arg in parse_shard_flags"),
+        };
+        assert_eq!(opts.shard, Some((1, 4)));
+
+        let args = vec!["progname".to_string(),
+                        "--shard-index".to_string(), "4".to_string(),
+                        "--shard-count".to_string(), "4".to_string()];
+        assert!(parse_opts(&args).unwrap().is_err());
+
+        let args = vec!["progname".to_string(), "--shard-count".to_string(), "4".to_string()];
+        assert!(parse_opts(&args).unwrap().is_err());
+    }
+
+    #[test]
+    pub fn shuffle_tests_with_seed() {
+        fn names(tests: Vec<TestDescAndFn>) -> Vec<String> {
+            tests.into_iter().map(|t| t.desc.name.to_string()).collect()
+        }
+
+        let sorted = names(filter_tests(&TestOpts::new(), numbered_tests(20)));
+        let shuffle = |seed| {
+            let opts = TestOpts { shuffle_seed: Some(seed), ..TestOpts::new() };
+            names(filter_tests(&opts, numbered_tests(20)))
+        };
+
+        let shuffled = shuffle(42);
+        assert!(shuffled != sorted);
+        assert_eq!(shuffled, shuffle(42));
+        assert!(shuffled != shuffle(43));
+
+        let mut resorted = shuffled.clone();
+        resorted.sort();
+        assert_eq!(resorted, sorted);
+    }
+
     #[test]
     pub fn test_metricmap_compare() {
         let mut m1 = MetricMap::new();
@@ -14,6 +14,8 @@
 use std::cmp::Ordering::{self, Equal, Greater, Less};
 use std::mem;
 
+use rand::{Rng, SeedableRng, XorShiftRng};
+
 fn local_cmp(x: f64, y: f64) -> Ordering {
     // arbitrarily decide that NaNs are larger than everything.
     if y.is_nan() {
@@ -325,12 +327,113 @@ pub fn winsorize(samples: &mut [f64], pct: f64) {
     }
 }
 
+/// Counts of the samples lying outside Tukey's fences. Samples more than 1.5
+/// inter-quartile ranges below the 1st or above the 3rd quartile are mild
+/// outliers, those more than 3 inter-quartile ranges away are severe ones.
+///
+/// See: https://en.wikipedia.org/wiki/Outlier#Tukey.27s_fences
+#[derive(Clone, PartialEq, Copy, Debug)]
+#[allow(missing_docs)]
+pub struct Outliers {
+    pub low_severe: usize,
+    pub low_mild: usize,
+    pub high_mild: usize,
+    pub high_severe: usize,
+}
+
+impl Outliers {
+    /// Classify the outliers of a sample set.
+    pub fn new(samples: &[f64]) -> Outliers {
+        let (q1, _, q3) = samples.quartiles();
+        let iqr = q3 - q1;
+        let mut outliers = Outliers {
+            low_severe: 0,
+            low_mild: 0,
+            high_mild: 0,
+            high_severe: 0,
+        };
+        for &samp in samples {
+            if samp < q1 - 3.0 * iqr {
+                outliers.low_severe += 1;
+            } else if samp < q1 - 1.5 * iqr {
+                outliers.low_mild += 1;
+            } else if samp > q3 + 3.0 * iqr {
+                outliers.high_severe += 1;
+            } else if samp > q3 + 1.5 * iqr {
+                outliers.high_mild += 1;
+            }
+        }
+        outliers
+    }
+
+    /// Number of mild outliers.
+    pub fn mild(&self) -> usize {
+        self.low_mild + self.high_mild
+    }
+
+    /// Number of severe outliers.
+    pub fn severe(&self) -> usize {
+        self.low_severe + self.high_severe
+    }
+
+    /// Number of outliers of either kind.
+    pub fn total(&self) -> usize {
+        self.mild() + self.severe()
+    }
+}
+
+/// A confidence interval: the true value of the estimated statistic lies
+/// between `lower` and `upper` with a probability of `level` percent.
+#[derive(Clone, PartialEq, Copy, Debug)]
+#[allow(missing_docs)]
+pub struct ConfidenceInterval {
+    pub lower: f64,
+    pub upper: f64,
+    pub level: f64,
+}
+
+/// Estimate a `level` percent confidence interval for the median of a sample
+/// set with the percentile bootstrap: the median is computed for `resamples`
+/// sets drawn from `samples` with replacement, and the interval spans the
+/// middle `level` percent of those medians. The resamples are drawn from a
+/// generator seeded with `seed`, so the same arguments give the same interval.
+///
+/// See: https://en.wikipedia.org/wiki/Bootstrapping_(statistics)
+pub fn bootstrap_median(samples: &[f64],
+                        level: f64,
+                        resamples: usize,
+                        seed: u64)
+                        -> ConfidenceInterval {
+    assert!(!samples.is_empty());
+    assert!(0.0 < level && level < 100.0);
+    assert!(resamples > 0);
+    // XorShift must not be seeded with all zeros.
+    let mut rng = XorShiftRng::from_seed([0x193a6754, 0xa8a7d469,
+                                          seed as u32, (seed >> 32) as u32]);
+    let mut resample = vec![0.0; samples.len()];
+    let mut medians = Vec::with_capacity(resamples);
+    for _ in 0..resamples {
+        for samp in &mut resample {
+            *samp = samples[rng.gen_range(0, samples.len())];
+        }
+        local_sort(&mut resample);
+        medians.push(percentile_of_sorted(&resample, 50.0));
+    }
+    local_sort(&mut medians);
+    let tail = (100.0 - level) / 2.0;
+    ConfidenceInterval {
+        lower: percentile_of_sorted(&medians, tail),
+        upper: percentile_of_sorted(&medians, 100.0 - tail),
+        level: level,
+    }
+}
+
 // Test vectors generated from R, using the script src/etc/stat-test-vectors.r.
 
 #[cfg(test)]
 mod tests {
     use stats::Stats;
-    use stats::Summary;
+    use stats::{Summary, Outliers, bootstrap_median};
     use std::f64;
     use std::io::prelude::*;
     use std::io;
@@ -874,6 +977,34 @@ mod tests {
     fn test_sum_f64_between_ints_that_sum_to_0() {
         assert_eq!([1e30f64, 1.2f64, -1e30f64].sum(), 1.2);
     }
+
+    #[test]
+    fn test_outliers() {
+        let mut val = (1..21).map(|i| i as f64).collect::<Vec<_>>();
+        val.extend_from_slice(&[-30.0, 35.0, 60.0]);
+        let outliers = Outliers::new(&val);
+        assert_eq!(outliers,
+                   Outliers {
+                       low_severe: 1,
+                       low_mild: 0,
+                       high_mild: 1,
+                       high_severe: 1,
+                   });
+        assert_eq!(outliers.total(), 3);
+    }
+
+    #[test]
+    fn test_bootstrap_median() {
+        let val = (0..100).map(|i| i as f64).collect::<Vec<_>>();
+        let ci = bootstrap_median(&val, 95.0, 1000, 0);
+        assert_eq!(ci, bootstrap_median(&val, 95.0, 1000, 0));
+        assert_eq!(ci.level, 95.0);
+        assert!(ci.lower < val.median() && val.median() < ci.upper);
+        assert!(ci.lower > 30.0 && ci.upper < 70.0);
+
+        let ci = bootstrap_median(&[5.0, 5.0, 5.0], 95.0, 100, 1);
+        assert_eq!((ci.lower, ci.upper), (5.0, 5.0));
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,92 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Whether casts from and to `isize` and `usize` truncate depends on the target,
+// so this is checked for a 64- and a 32-bit one. Without the standard library,
+// which may not be there for the other target, the operators need lang items.
+
+// revisions: x64 x32
+//[x64] compile-flags: --target x86_64-unknown-linux-gnu
+//[x32] compile-flags: --target i686-unknown-linux-gnu
+
+#![feature(no_core, lang_items)]
+#![no_core]
+#![crate_type = "lib"]
+#![deny(lossy_integer_cast, sign_changing_cast, float_to_int_cast)]
+#![allow(dead_code)]
+
+#[lang = "sized"]
+trait Sized {}
+
+#[lang = "copy"]
+trait Copy {}
+
+#[lang = "neg"]
+trait Neg {
+    type Output;
+    fn neg(self) -> Self::Output;
+}
+
+impl Neg for i32 {
+    type Output = i32;
+    fn neg(self) -> i32 { -self }
+}
+
+#[lang = "bitand"]
+trait BitAnd<RHS = Self> {
+    type Output;
+    fn bitand(self, rhs: RHS) -> Self::Output;
+}
+
+impl BitAnd for u64 {
+    type Output = u64;
+    fn bitand(self, rhs: u64) -> u64 { self & rhs }
+}
+
+impl BitAnd for i64 {
+    type Output = i64;
+    fn bitand(self, rhs: i64) -> i64 { self & rhs }
+}
+
+fn lossy(a: u64, b: i64, c: usize) {
+    let _ = a as u32; //~ ERROR casting `u64` to `u32` may truncate the value
+    let _ = b as u8; //~ ERROR casting `i64` to `u8` may truncate the value
+    let _ = b as i16; //~ ERROR casting `i64` to `i16` may truncate the value
+    let _ = 300u32 as u8; //~ ERROR casting `u32` to `u8` may truncate the value
+
+    // Widening, masked and constant values that fit are fine.
+    let _ = c as u64;
+    let _ = (a & 0xffff_ffff) as u32;
+    let _ = (0xff & b) as u8;
+    let _ = 255u32 as u8;
+}
+
+fn lossy_pointer_sized(a: u64, b: i64, c: usize) {
+    let _ = c as u32; //[x64]~ ERROR casting `usize` to `u32` may truncate the value
+    let _ = a as usize; //[x32]~ ERROR casting `u64` to `usize` may truncate the value
+    let _ = b as isize; //[x32]~ ERROR casting `i64` to `isize` may truncate the value
+}
+
+fn sign_changing(a: i32, b: u32, c: i8) {
+    let _ = a as u32; //~ ERROR casting `i32` to `u32` may change the sign of the value
+    let _ = b as i32; //~ ERROR casting `u32` to `i32` may change the sign of the value
+    let _ = c as u64; //~ ERROR casting `i8` to `u64` may change the sign of the value
+    let _ = -1i32 as u32; //~ ERROR casting `i32` to `u32` may change the sign of the value
+
+    let _ = b as i64;
+    let _ = 7i32 as u32;
+}
+
+fn float_to_int(a: f64, b: f32) {
+    let _ = a as i32; //~ ERROR casting `f64` to `i32` truncates the value
+    let _ = b as u8; //~ ERROR casting `f32` to `u8` truncates the value
+
+    let _ = a as f32;
+}
@@ -0,0 +1,74 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(fn_complexity_limits)]
+#![cyclomatic_complexity_limit = "3"]
+#![fn_statement_limit = "8"]
+#![deny(cyclomatic_complexity, too_many_statements)]
+#![allow(dead_code)]
+
+fn simple(x: Option<u32>, y: bool) -> u32 {
+    if y {
+        return 1;
+    }
+    match x {
+        Some(x) => x,
+        None => 0,
+    }
+}
+
+fn matches(x: Option<u32>, y: bool) -> u32 {
+//~^ ERROR function has a cyclomatic complexity of 5, which exceeds the limit of 3
+    if y {
+        return 1;
+    }
+    match x {
+        Some(x) if x > 10 => 10,
+        Some(x) => x,
+        None => 0,
+    }
+}
+
+fn loops(v: &[u32]) -> u32 {
+//~^ ERROR function has a cyclomatic complexity of 4, which exceeds the limit of 3
+    let mut n = 0;
+    for &x in v {
+        n += x;
+    }
+    while n > 100 {
+        n /= 2;
+    }
+    loop {
+        break;
+    }
+    n
+}
+
+fn tries(a: Result<u32, ()>, b: Result<u32, ()>) -> Result<u32, ()> {
+//~^ ERROR function has a cyclomatic complexity of 4, which exceeds the limit of 3
+    let a = a?;
+    let b = try!(b);
+    Ok(if a > b { a } else { b })
+}
+
+fn long() -> u32 { //~ ERROR function has 9 statements, which exceeds the limit of 8
+    let a = 1;
+    let b = a + 1;
+    let c = b + 1;
+    let d = c + 1;
+    let e = d + 1;
+    let f = e + 1;
+    let g = f + 1;
+    let h = g + 1;
+    let i = h + 1;
+    i
+}
+
+fn main() {}
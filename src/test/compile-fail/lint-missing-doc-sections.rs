@@ -0,0 +1,83 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![crate_type = "lib"]
+#![deny(missing_errors_doc, missing_panics_doc)]
+
+/// Parses a number.
+pub fn parse(s: &str) -> Result<u32, ::std::num::ParseIntError> {
+//~^ ERROR docs for function returning `Result` missing `# Errors` section
+    s.parse()
+}
+
+/// Parses a number.
+///
+/// # Errors
+///
+/// Fails if `s` is not a number.
+pub fn parse_documented(s: &str) -> Result<u32, ::std::num::ParseIntError> {
+    s.parse()
+}
+
+/// Returns the first element.
+pub fn first(v: &[u32]) -> u32 {
+//~^ ERROR docs for function which may panic missing `# Panics` section
+    v[0]
+}
+
+/// Returns the number.
+pub fn number(s: &str) -> u32 {
+//~^ ERROR docs for function which may panic missing `# Panics` section
+    s.parse().unwrap()
+}
+
+/// Checks the number.
+pub fn check(n: u32) {
+//~^ ERROR docs for function which may panic missing `# Panics` section
+    assert!(n > 1, "too small");
+}
+
+/// Returns the first element.
+///
+/// # Panics
+///
+/// Panics if `v` is empty.
+pub fn first_documented(v: &[u32]) -> u32 {
+    v[0]
+}
+
+/// Returns a copy of the slice.
+pub fn copy(v: &[u32]) -> Vec<u32> {
+    v[..].to_vec()
+}
+
+pub struct Wrapper(Option<u32>);
+
+impl Wrapper {
+    /// Returns the value.
+    pub fn get(&self) -> u32 { //~ ERROR docs for function which may panic missing `# Panics`
+        self.0.expect("no value")
+    }
+
+    /// Returns the value, or `default`.
+    pub fn get_or(&self, default: u32) -> u32 {
+        self.0.unwrap_or(default)
+    }
+}
+
+// Private functions aren't documented for users.
+/// Returns the first element.
+fn private_first(v: &[u32]) -> u32 {
+    v[0]
+}
+
+pub fn use_private() -> u32 {
+    private_first(&[1])
+}
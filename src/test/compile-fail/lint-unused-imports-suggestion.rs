@@ -0,0 +1,33 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Unused `use` items come with a suggestion to remove them, unless some of
+// their imports are used.
+
+#![deny(unused_imports)]
+
+use std::fmt;
+//~^ ERROR unused import
+//~| HELP remove the unused import
+
+use std::cmp::{max, min};
+//~^ ERROR unused imports
+//~| HELP remove the unused import
+
+use std::mem::{replace, swap};
+//~^ ERROR unused import
+
+#[allow(dead_code)]
+use std::ptr;
+//~^ ERROR unused import
+
+fn main() {
+    swap(&mut 1, &mut 2);
+}
@@ -0,0 +1,56 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: --test
+
+#![deny(unwrap_used)]
+#![allow(dead_code)]
+
+struct Wrapper(Option<u32>);
+
+impl Wrapper {
+    // Not `Option::unwrap`, even though it has the same name.
+    fn unwrap(self) -> u32 {
+        self.0.unwrap_or(0)
+    }
+}
+
+trait Unwrap {
+    fn unwrap(self) -> u32;
+}
+
+impl Unwrap for Result<u32, ()> {
+    fn unwrap(self) -> u32 {
+        self.unwrap_or(0)
+    }
+}
+
+fn production(o: Option<u32>, r: Result<u32, String>) -> u32 {
+    let a = o.unwrap(); //~ ERROR used `Option::unwrap`, which may panic
+    let b = o.expect("no value"); //~ ERROR used `Option::expect`, which may panic
+    let c = r.clone().unwrap(); //~ ERROR used `Result::unwrap`, which may panic
+    let d = r.expect("no value"); //~ ERROR used `Result::expect`, which may panic
+    let e = Option::unwrap(o); //~ ERROR used `Option::unwrap`, which may panic
+    let f = Wrapper(o).unwrap();
+    let g = Unwrap::unwrap(Ok::<u32, ()>(1));
+    a + b + c + d + e + f + g + o.unwrap_or(0)
+}
+
+#[test]
+fn unwrap_in_test() {
+    assert_eq!(Some(1).unwrap(), 1);
+}
+
+#[cfg(test)]
+mod tests {
+    fn helper() -> u32 {
+        "1".parse::<u32>().unwrap()
+    }
+}
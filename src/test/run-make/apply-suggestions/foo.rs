@@ -0,0 +1,21 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Two unused `use` items, with suggestions to remove them, around a used one.
+
+use std::cmp::{max, min};
+use std::mem::swap;
+use std::fmt;
+
+fn main() {
+    let (mut a, mut b) = ("ä", "ö");
+    swap(&mut a, &mut b);
+    assert_eq!((a, b), ("ö", "ä"));
+}
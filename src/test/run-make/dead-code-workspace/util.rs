@@ -0,0 +1,31 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![crate_type = "lib"]
+
+pub struct Config {
+    pub verbose: bool,
+}
+
+pub struct Unused;
+
+pub fn used_helper(config: &Config) -> bool {
+    internal(config)
+}
+
+pub fn unused_helper() {}
+
+fn internal(config: &Config) -> bool {
+    config.verbose
+}
+
+// Changes the crate, and with it its hash.
+#[cfg(changed)]
+pub fn added() {}
@@ -0,0 +1,21 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Each use of the macro is an error at the same place in its definition.
+macro_rules! missing {
+    () => { not_defined }
+}
+
+fn main() {
+    let _a: u32 = missing!();
+    let _b: u32 = missing!();
+    let _c: u32 = missing!();
+    let _d: u32 = also_not_defined;
+}
@@ -0,0 +1,26 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+macro_rules! inner {
+    () => { not_defined }
+}
+
+macro_rules! count_down {
+    () => { inner!() };
+    ($head:tt $($tail:tt)*) => { count_down!($($tail)*) };
+}
+
+macro_rules! outer {
+    () => { count_down!(a b c) }
+}
+
+fn main() {
+    let _x: u32 = outer!();
+}
@@ -0,0 +1,65 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![crate_name = "foo"]
+
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+}
+
+impl Point {
+    pub fn new() -> Point {
+        Point { x: 0, y: 0, z: 0 }
+    }
+
+    pub fn origin() -> Point {
+        Point::new()
+    }
+}
+
+pub struct Opaque {
+    pub x: i32,
+    pub w: i32,
+    y: i32,
+}
+
+pub enum Shape {
+    Circle,
+    Square,
+    Triangle,
+}
+
+pub trait Area {
+    fn area(&self) -> f64;
+    fn name(&self) -> String;
+    fn perimeter(&self) -> f64 {
+        0.0
+    }
+}
+
+pub fn changed(x: i64) -> i64 {
+    x
+}
+
+pub fn renamed_argument(y: i32) -> i32 {
+    y
+}
+
+pub fn added() {}
+
+pub struct Wrapper<T>(pub T);
+
+impl<U> Wrapper<U> {
+    pub fn is_wrapped(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,60 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![crate_name = "foo"]
+
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Point {
+    pub fn new() -> Point {
+        Point { x: 0, y: 0 }
+    }
+}
+
+impl Clone for Point {
+    fn clone(&self) -> Point {
+        Point { x: self.x, y: self.y }
+    }
+}
+
+pub struct Opaque {
+    pub x: i32,
+    y: i32,
+}
+
+pub enum Shape {
+    Circle,
+    Square,
+}
+
+pub trait Area {
+    fn area(&self) -> f64;
+}
+
+pub fn removed() {}
+
+pub fn changed(x: i32) -> i32 {
+    x
+}
+
+pub fn renamed_argument(x: i32) -> i32 {
+    x
+}
+
+pub struct Wrapper<T>(pub T);
+
+impl<T> Wrapper<T> {
+    pub fn is_wrapped(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,30 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The crate docs.
+
+/// A point on the plane.
+///
+/// ```
+/// let p = foo::Point { x: 1, y: 2 };
+/// ```
+pub struct Point {
+    /// The horizontal coordinate.
+    pub x: i32,
+    pub y: i32,
+}
+
+pub mod shapes {
+    /// The area of the shape.
+    pub fn area() {}
+    pub fn perimeter() {}
+}
+
+fn private() {}
@@ -0,0 +1,59 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![crate_name = "foo"]
+
+//! Links to [`Bar::new`] and [`bar::baz`].
+
+/// Links to [`Bar::x`], [`Shape::Circle`], [the trait](type@Area) and [`Missing`].
+pub struct Bar {
+    pub x: i32,
+}
+
+impl Bar {
+    pub fn new() -> Bar {
+        Bar { x: 0 }
+    }
+}
+
+/// The fields of [`Alias::x`] are those of the type it stands for.
+pub enum Shape {
+    Circle,
+}
+
+impl Shape {
+    /// Whether this is a [`Self::Circle`].
+    pub fn is_circle(&self) -> bool {
+        true
+    }
+}
+
+pub type Alias = Bar;
+
+/// Links to [`thing`], [the module](type@thing) and [the function](thing()).
+pub trait Area {
+    fn area(&self) -> f64;
+}
+
+pub mod thing {}
+
+pub fn thing() {}
+
+pub mod bar {
+    /// Links to [`super::Bar`] and [`Option`].
+    pub fn baz() {}
+}
+
+pub mod shapes {
+    pub use Shape::*;
+
+    /// Links to [`Circle`], which is imported with a glob.
+    pub fn circle() {}
+}
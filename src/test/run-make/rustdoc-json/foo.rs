@@ -0,0 +1,43 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![crate_name = "foo"]
+
+//! The crate documentation.
+
+/// A point on the plane.
+pub struct Point<T> where T: Copy {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T: Copy> Clone for Point<T> {
+    fn clone(&self) -> Point<T> {
+        Point { x: self.x, y: self.y }
+    }
+}
+
+pub mod shapes {
+    /// Something with an area.
+    pub trait Area {
+        fn area(&self) -> f64;
+    }
+
+    pub struct Square(pub f64);
+
+    impl Area for Square {
+        fn area(&self) -> f64 {
+            self.0 * self.0
+        }
+    }
+}
+
+#[doc(hidden)]
+pub fn hidden() {}
@@ -0,0 +1,30 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! See [the struct](struct.Foo.html), [a missing page](struct.Missing.html)
+//! and [a dead anchor](struct.Foo.html#method.missing).
+
+/// A unit struct.
+///
+/// Links to [a method](#method.new), [nothing](#nothing), [the module](index.html)
+/// and [itself](../foo/struct.Foo.html).
+pub struct Foo;
+
+impl Foo {
+    pub fn new() -> Foo {
+        Foo
+    }
+}
+
+/// Links to [another crate](../other/index.html),
+/// [a dead anchor in it](../other/struct.Other.html#method.missing),
+/// [a crate which isn't documented yet](../later/index.html) and
+/// [another site](https://example.com/missing.html).
+pub fn bar() {}
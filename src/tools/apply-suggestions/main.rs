@@ -0,0 +1,294 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Tool to apply the suggestions of rustc's diagnostics to the source files.
+//!
+//! The diagnostics are read in the format of `rustc --error-format=json`,
+//! from the files given as arguments or from standard input:
+//!
+//! ```text
+//! rustc --error-format=json foo.rs 2>&1 | apply-suggestions
+//! ```
+//!
+//! Only the suggestions rustc marks as `machine_applicable` are applied, the
+//! others need a human to look at them. Lines which aren't diagnostics are
+//! ignored, so the output of a whole build can be fed in. File names are
+//! taken as rustc printed them, so this has to run in the directory rustc was
+//! run in.
+
+extern crate rustc_serialize;
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::{self, BufReader};
+use std::iter;
+use std::process;
+
+use rustc_serialize::json::Json;
+
+macro_rules! t {
+    ($e:expr) => (match $e {
+        Ok(e) => e,
+        Err(e) => panic!("{} failed with {}", stringify!($e), e),
+    })
+}
+
+/// A suggested replacement of a part of a file. Positions are 1-based
+/// `(line, column)` pairs, columns counting characters as in the JSON output.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug)]
+struct Replacement {
+    start: (usize, usize),
+    end: (usize, usize),
+    text: String,
+}
+
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        println!("Usage: apply-suggestions [DIAGNOSTICS...]");
+        println!("");
+        println!("Applies the machine-applicable suggestions of the JSON diagnostics read");
+        println!("from DIAGNOSTICS, or from stdin if no files are given.");
+        return;
+    }
+
+    let mut replacements = BTreeMap::new();
+    if args.is_empty() {
+        let stdin = io::stdin();
+        collect(stdin.lock(), &mut replacements);
+    } else {
+        for arg in &args {
+            collect(BufReader::new(t!(File::open(arg))), &mut replacements);
+        }
+    }
+
+    let mut errors = false;
+    for (file, replacements) in replacements {
+        match apply(&file, replacements) {
+            Ok(0) => {}
+            Ok(n) => println!("{}: applied {} suggestion(s)", file, n),
+            Err(e) => {
+                let _ = writeln!(io::stderr(), "error: failed to fix {}: {}", file, e);
+                errors = true;
+            }
+        }
+    }
+    if errors {
+        process::exit(1);
+    }
+}
+
+fn collect<R: BufRead>(input: R, replacements: &mut BTreeMap<String, Vec<Replacement>>) {
+    for line in input.lines() {
+        if let Ok(diagnostic) = t!(line).parse::<Json>() {
+            collect_diagnostic(&diagnostic, replacements);
+        }
+    }
+}
+
+fn collect_diagnostic(diagnostic: &Json, replacements: &mut BTreeMap<String, Vec<Replacement>>) {
+    let spans = diagnostic.find("spans").and_then(|spans| spans.as_array());
+    for span in spans.into_iter().flat_map(|spans| spans) {
+        if let Some((file, replacement)) = replacement(span) {
+            replacements.entry(file).or_insert_with(Vec::new).push(replacement);
+        }
+    }
+    let children = diagnostic.find("children").and_then(|children| children.as_array());
+    for child in children.into_iter().flat_map(|children| children) {
+        collect_diagnostic(child, replacements);
+    }
+}
+
+fn replacement(span: &Json) -> Option<(String, Replacement)> {
+    if span.find("machine_applicable").and_then(|a| a.as_boolean()) != Some(true) {
+        return None;
+    }
+    // Code expanded from a macro can't be fixed where it is reported.
+    if span.find("expansion").map_or(false, |e| !e.is_null()) {
+        return None;
+    }
+    let position = |line: &str, column: &str| {
+        match (span.find(line).and_then(|l| l.as_u64()),
+               span.find(column).and_then(|c| c.as_u64())) {
+            (Some(line), Some(column)) => Some((line as usize, column as usize)),
+            _ => None,
+        }
+    };
+    let file = span.find("file_name").and_then(|f| f.as_string());
+    let text = span.find("suggested_replacement").and_then(|t| t.as_string());
+    match (file, position("line_start", "column_start"), position("line_end", "column_end"), text) {
+        (Some(file), Some(start), Some(end), Some(text)) => {
+            Some((file.to_owned(), Replacement {
+                start: start,
+                end: end,
+                text: text.to_owned(),
+            }))
+        }
+        _ => None,
+    }
+}
+
+/// Applies `replacements` to `file`, returning how many of them were applied.
+fn apply(file: &str, replacements: Vec<Replacement>) -> io::Result<usize> {
+    let mut contents = String::new();
+    File::open(file)?.read_to_string(&mut contents)?;
+    let (fixed, applied) = apply_to(file, &contents, replacements);
+    if applied != 0 {
+        File::create(file)?.write_all(fixed.as_bytes())?;
+    }
+    Ok(applied)
+}
+
+/// Applies `replacements` to `contents`, the contents of `file`, returning
+/// the fixed contents and how many replacements were applied. Replacements
+/// overlapping an earlier one are skipped with a warning, they can be applied
+/// by running rustc and this tool again.
+fn apply_to(file: &str, contents: &str, mut replacements: Vec<Replacement>) -> (String, usize) {
+    // The same diagnostic may well have been reported more than once, e.g.
+    // by builds for several targets.
+    replacements.sort();
+    replacements.dedup();
+
+    let line_starts = iter::once(0)
+        .chain(contents.match_indices('\n').map(|(i, _)| i + 1))
+        .collect::<Vec<_>>();
+    let offset = |(line, column): (usize, usize)| {
+        if line == 0 || column == 0 || line > line_starts.len() {
+            return None;
+        }
+        let start = line_starts[line - 1];
+        contents[start..].char_indices()
+                         .map(|(i, _)| start + i)
+                         .chain(iter::once(contents.len()))
+                         .nth(column - 1)
+    };
+
+    let mut edits = Vec::new();
+    let mut prev_end = 0;
+    for replacement in replacements {
+        let (mut start, mut end) = match (offset(replacement.start), offset(replacement.end)) {
+            (Some(start), Some(end)) if start <= end => (start, end),
+            _ => {
+                warn(file, &replacement, "does not fit the file, was it changed?");
+                continue;
+            }
+        };
+        // Don't leave an empty line behind when removing all of its code.
+        if replacement.text.is_empty() {
+            let line_start = contents[..start].rfind('\n').map_or(0, |i| i + 1);
+            let line_end = contents[end..].find('\n').map_or(contents.len(), |i| end + i + 1);
+            if contents[line_start..start].trim().is_empty() &&
+               contents[end..line_end].trim().is_empty() {
+                start = line_start;
+                end = line_end;
+            }
+        }
+        if start < prev_end {
+            warn(file, &replacement, "overlaps with an earlier suggestion");
+            continue;
+        }
+        prev_end = end;
+        edits.push((start, end, replacement.text));
+    }
+
+    let mut fixed = String::with_capacity(contents.len());
+    let mut last = 0;
+    for &(start, end, ref text) in &edits {
+        fixed.push_str(&contents[last..start]);
+        fixed.push_str(text);
+        last = end;
+    }
+    fixed.push_str(&contents[last..]);
+    (fixed, edits.len())
+}
+
+fn warn(file: &str, replacement: &Replacement, msg: &str) {
+    let _ = writeln!(io::stderr(),
+                     "warning: {}:{}:{}: skipping suggestion that {}",
+                     file,
+                     replacement.start.0,
+                     replacement.start.1,
+                     msg);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{apply_to, Replacement};
+
+    fn replacement(start: (usize, usize), end: (usize, usize), text: &str) -> Replacement {
+        Replacement {
+            start: start,
+            end: end,
+            text: text.to_owned(),
+        }
+    }
+
+    #[test]
+    fn single_replacement() {
+        let contents = "fn main() {\n    let x = t[0];\n}\n";
+        let fixed = apply_to("foo.rs", contents, vec![replacement((2, 13), (2, 17), "t.0")]);
+        assert_eq!(fixed, ("fn main() {\n    let x = t.0;\n}\n".to_owned(), 1));
+    }
+
+    #[test]
+    fn multiple_replacements() {
+        let contents = "a b c\nd e f\n";
+        // Given out of order, and one of them twice.
+        let replacements = vec![replacement((2, 3), (2, 4), "E"),
+                                replacement((1, 1), (1, 2), "A"),
+                                replacement((2, 3), (2, 4), "E"),
+                                replacement((1, 5), (1, 6), "C")];
+        assert_eq!(apply_to("foo.rs", contents, replacements),
+                   ("A b C\nd E f\n".to_owned(), 3));
+    }
+
+    #[test]
+    fn overlapping_replacements() {
+        let contents = "abcdef\n";
+        let replacements = vec![replacement((1, 2), (1, 5), "X"),
+                                replacement((1, 4), (1, 6), "Y"),
+                                replacement((1, 6), (1, 7), "Z")];
+        assert_eq!(apply_to("foo.rs", contents, replacements),
+                   ("aXeZ\n".to_owned(), 2));
+    }
+
+    #[test]
+    fn multibyte_text() {
+        // Columns count characters, not bytes.
+        let contents = "let s = \"äöü\"; let x = t[0];\n";
+        let fixed = apply_to("foo.rs", contents, vec![replacement((1, 24), (1, 28), "t.0")]);
+        assert_eq!(fixed, ("let s = \"äöü\"; let x = t.0;\n".to_owned(), 1));
+
+        let fixed = apply_to("foo.rs", contents, vec![replacement((1, 10), (1, 13), "ß")]);
+        assert_eq!(fixed, ("let s = \"ß\"; let x = t[0];\n".to_owned(), 1));
+    }
+
+    #[test]
+    fn removed_lines() {
+        let contents = "use a;\n    use b;\nuse c; use d;\n";
+        // Removing all of a line's code removes the line, otherwise only the
+        // code is removed.
+        let replacements = vec![replacement((2, 5), (2, 11), ""),
+                                replacement((3, 1), (3, 7), "")];
+        assert_eq!(apply_to("foo.rs", contents, replacements),
+                   ("use a;\n use d;\n".to_owned(), 2));
+    }
+
+    #[test]
+    fn out_of_range() {
+        let contents = "abc\n";
+        let replacements = vec![replacement((3, 1), (3, 2), "X"),
+                                replacement((1, 3), (1, 2), "Y"),
+                                replacement((1, 1), (1, 9), "Z")];
+        assert_eq!(apply_to("foo.rs", contents, replacements), ("abc\n".to_owned(), 0));
+    }
+}
@@ -102,6 +102,9 @@ pub struct Config {
     // The llvm FileCheck binary path
     pub llvm_filecheck: Option<PathBuf>,
 
+    // The apply-suggestions tool path, for run-make tests
+    pub apply_suggestions_path: Option<PathBuf>,
+
     // The valgrind path
     pub valgrind_path: Option<String>,
 
@@ -86,6 +86,7 @@ pub fn parse_config(args: Vec<String> ) -> Config {
           optopt("", "valgrind-path", "path to Valgrind executable for Valgrind tests", "PROGRAM"),
           optflag("", "force-valgrind", "fail if Valgrind tests cannot be run under Valgrind"),
           optopt("", "llvm-filecheck", "path to LLVM's FileCheck binary", "DIR"),
+          optopt("", "apply-suggestions-path", "path to the apply-suggestions tool", "PATH"),
           reqopt("", "src-base", "directory to scan for test files", "PATH"),
           reqopt("", "build-base", "directory to deposit test outputs", "PATH"),
           reqopt("", "stage-id", "the target-stage identifier", "stageN-TARGET"),
@@ -166,6 +167,7 @@ pub fn parse_config(args: Vec<String> ) -> Config {
         valgrind_path: matches.opt_str("valgrind-path"),
         force_valgrind: matches.opt_present("force-valgrind"),
         llvm_filecheck: matches.opt_str("llvm-filecheck").map(|s| PathBuf::from(&s)),
+        apply_suggestions_path: matches.opt_str("apply-suggestions-path").map(PathBuf::from),
         src_base: opt_path(matches, "src-base"),
         build_base: opt_path(matches, "build-base"),
         stage_id: matches.opt_str("stage-id").unwrap(),
@@ -339,7 +341,10 @@ pub fn test_opts(config: &Config) -> test::TestOpts {
         filter_exact: config.filter_exact,
         run_ignored: config.run_ignored,
         quiet: config.quiet,
+        report_time: false,
+        slowest: None,
         logfile: config.logfile.clone(),
+        junit_xml: None,
         run_tests: true,
         bench_benchmarks: true,
         nocapture: match env::var("RUST_TEST_NOCAPTURE") {
@@ -347,9 +352,18 @@ pub fn test_opts(config: &Config) -> test::TestOpts {
             Err(_) => false
         },
         color: test::AutoColor,
+        format: test::OutputFormat::Pretty,
         test_threads: None,
         skip: vec![],
         list: false,
+        isolate: false,
+        test_timeout: None,
+        shuffle_seed: None,
+        shard: None,
+        bench_config: test::bench::BenchConfig::default(),
+        save_baseline: None,
+        baseline: None,
+        noise_threshold: 5.0,
     }
 }
 
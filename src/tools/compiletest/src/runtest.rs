@@ -2236,6 +2236,10 @@ actual:\n\
            .env("LLVM_COMPONENTS", &self.config.llvm_components)
            .env("LLVM_CXXFLAGS", &self.config.llvm_cxxflags);
 
+        if let Some(ref apply_suggestions) = self.config.apply_suggestions_path {
+            cmd.env("APPLY_SUGGESTIONS", cwd.join(apply_suggestions));
+        }
+
         // We don't want RUSTFLAGS set from the outside to interfere with
         // compiler flags set in the test cases:
         cmd.env_remove("RUSTFLAGS");